@@ -0,0 +1,19 @@
+/// Read-only input registers which a program can copy onto its stacks any number of times.
+///
+/// These hold the inputs of a problem, so a single program can be evaluated on many test cases by changing only the
+/// `Machine`'s inputs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inputs {
+    pub ints: Vec<i64>,
+    pub floats: Vec<f64>,
+    pub bools: Vec<bool>,
+    pub int_vecs: Vec<Vec<i64>>,
+    pub float_vecs: Vec<Vec<f64>>,
+    pub bool_vecs: Vec<Vec<bool>>,
+}
+
+impl Inputs {
+    pub fn new() -> Inputs {
+        Inputs::default()
+    }
+}
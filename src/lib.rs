@@ -7,12 +7,18 @@ extern crate serde;
 mod vec;
 mod mem;
 mod state;
+mod trace;
+mod inputs;
 pub mod simple;
 
-use state::*;
+pub use state::*;
+pub use trace::*;
+pub use inputs::Inputs;
+pub use vec::{TrackedVec, TrackedIter, TrackedCycleIter};
 
 use mem::TotalMemory;
 use heapsize::HeapSizeOf;
+use std::cmp;
 
 /// A Gapush `Machine` is a state machine which tracks the memory consumption of an arbitrary program and executes it
 /// without going over a specified limit amount of memory (`max_memory`).
@@ -20,7 +26,9 @@ use heapsize::HeapSizeOf;
 pub struct Machine<Ins, InsHandler, IntHandler, FloatHandler> {
     /// The internal state which instructions operate on.
     pub state: State<Ins>,
-    
+    /// The input registers the program can read from.
+    pub inputs: Inputs,
+
     /// This is called to produce an instruction when one wasn't available.
     pub ins_handler: InsHandler,
     /// This is called to produce an integer when one wasn't available.
@@ -35,6 +43,7 @@ impl<I, IH, IntH, FloatH> Machine<I, IH, IntH, FloatH>
     pub fn new(max_size: usize, ins_handler: IH, int_handler: IntH, float_handler: FloatH) -> Self {
         Machine {
             state: State::new(max_size),
+            inputs: Inputs::new(),
             ins_handler: ins_handler,
             int_handler: int_handler,
             float_handler: float_handler,
@@ -42,13 +51,128 @@ impl<I, IH, IntH, FloatH> Machine<I, IH, IntH, FloatH>
     }
 
     /// Run a cycle of the machine unconditionally, executing an instruction produced by the instruction handler if
-    /// necessary, and return whether or not the instruction executed was successful.
-    pub fn cycle(&mut self) -> (Option<I>, bool)
+    /// necessary, and return the instruction provided to the caller of the machine, if any, and whether the
+    /// instruction succeeded, or the `Fault` which prevented it from executing cleanly.
+    pub fn cycle(&mut self) -> (Option<I>, Result<(), Fault>)
         where I: Instruction<IH, IntH, FloatH>
     {
         self.state.pop_exe().unwrap_or_else(&mut self.ins_handler).operate(self)
     }
 
+    /// Run a cycle of the machine like `cycle`, reporting the instruction executed and its effect to `tracer`.
+    pub fn cycle_traced<T>(&mut self, tracer: &mut T) -> (Option<I>, Result<(), Fault>)
+        where I: Instruction<IH, IntH, FloatH>,
+              T: Tracer<I>
+    {
+        let ins = self.state.pop_exe().unwrap_or_else(&mut self.ins_handler);
+        tracer.before(&ins, self.state.size(), &self.state.depths());
+        let (provided, result) = ins.operate(self);
+        tracer.after(result, provided.as_ref(), self.state.size(), &self.state.depths());
+        (provided, result)
+    }
+
+    /// Execute instructions until the exe stack is empty, an instruction is provided, `max_steps` instructions
+    /// have executed or the state runs out of memory.
+    ///
+    /// Unlike `cycle`, this never calls the instruction handler to produce an instruction.
+    pub fn run(&mut self, max_steps: usize) -> RunOutcome<I>
+        where I: Instruction<IH, IntH, FloatH>
+    {
+        self.run_traced(max_steps, &mut ())
+    }
+
+    /// Execute instructions like `run`, reporting each instruction executed and its effect to `tracer`.
+    pub fn run_traced<T>(&mut self, max_steps: usize, tracer: &mut T) -> RunOutcome<I>
+        where I: Instruction<IH, IntH, FloatH>,
+              T: Tracer<I>
+    {
+        let mut outcome = RunOutcome {
+            termination: Termination::StepLimit,
+            steps: 0,
+            failures: 0,
+            handled: 0,
+            peak_memory: self.state.size(),
+        };
+        while outcome.steps < max_steps {
+            outcome.peak_memory = cmp::max(outcome.peak_memory, self.state.size());
+            let ins = match self.state.pop_exe() {
+                Some(ins) => ins,
+                None => {
+                    outcome.termination = Termination::Halted;
+                    break;
+                }
+            };
+            tracer.before(&ins, self.state.size(), &self.state.depths());
+            let (provided, result) = ins.operate(self);
+            tracer.after(result, provided.as_ref(), self.state.size(), &self.state.depths());
+            outcome.steps += 1;
+            outcome.peak_memory = cmp::max(outcome.peak_memory, self.state.size());
+            if let Some(ins) = provided {
+                outcome.termination = Termination::Provided(ins);
+                break;
+            }
+            match result {
+                Ok(()) => {}
+                Err(fault) if fault.is_handled() => outcome.handled += 1,
+                Err(Fault::Full) => {
+                    outcome.failures += 1;
+                    outcome.termination = Termination::OutOfMemory;
+                    break;
+                }
+                Err(_) => outcome.failures += 1,
+            }
+        }
+        outcome
+    }
+
+    /// Pop an integer, or produce one with the int handler if the stack is empty and record the underflow in
+    /// `fault`.
+    pub fn pop_int_or_handle(&mut self, fault: &mut Option<Fault>) -> i64 {
+        match self.state.pop_int() {
+            Some(int) => int,
+            None => {
+                *fault = Some(Fault::Underflow);
+                (self.int_handler)()
+            }
+        }
+    }
+
+    /// Pop a float, or produce one with the float handler if the stack is empty and record the underflow in
+    /// `fault`.
+    pub fn pop_float_or_handle(&mut self, fault: &mut Option<Fault>) -> f64 {
+        match self.state.pop_float() {
+            Some(float) => float,
+            None => {
+                *fault = Some(Fault::Underflow);
+                (self.float_handler)()
+            }
+        }
+    }
+
+    /// Use the result of an integer operation, or produce one with the int handler if the result was undefined and
+    /// record that in `fault`.
+    pub fn int_or_handle(&mut self, int: Option<i64>, fault: &mut Option<Fault>) -> i64 {
+        match int {
+            Some(int) => int,
+            None => {
+                *fault = Some(Fault::Undefined);
+                (self.int_handler)()
+            }
+        }
+    }
+
+    /// Use the result of a float operation, or produce one with the float handler if the result was undefined and
+    /// record that in `fault`.
+    pub fn float_or_handle(&mut self, float: Option<f64>, fault: &mut Option<Fault>) -> f64 {
+        match float {
+            Some(float) => float,
+            None => {
+                *fault = Some(Fault::Undefined);
+                (self.float_handler)()
+            }
+        }
+    }
+
     /// Provide instruction, returning true if successful.
     pub fn provide(&mut self, ins: I) -> bool
         where I: Instruction<IH, IntH, FloatH>
@@ -77,9 +201,85 @@ impl<I, IH, IntH, FloatH> Machine<I, IH, IntH, FloatH>
     }
 }
 
+/// The reason `Machine::run` stopped executing.
+#[derive(Debug, Clone)]
+pub enum Termination<I> {
+    /// The exe stack became empty.
+    Halted,
+    /// The step budget was used up.
+    StepLimit,
+    /// An instruction failed because the state ran out of memory.
+    OutOfMemory,
+    /// The program provided an instruction.
+    Provided(I),
+}
+
+/// A summary of a call to `Machine::run`.
+#[derive(Debug, Clone)]
+pub struct RunOutcome<I> {
+    pub termination: Termination<I>,
+    /// The number of instructions executed.
+    pub steps: usize,
+    /// The number of executed instructions which failed.
+    pub failures: usize,
+    /// The number of executed instructions which only took effect because a handler supplied a value.
+    pub handled: usize,
+    /// The most memory the state used at any point between instructions.
+    pub peak_memory: usize,
+}
+
+impl<I> RunOutcome<I> {
+    /// The instruction the program provided, if that is why it stopped.
+    pub fn provided(&self) -> Option<&I> {
+        match self.termination {
+            Termination::Provided(ref ins) => Some(ins),
+            _ => None,
+        }
+    }
+}
+
+/// The reason an instruction did not execute cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Fault {
+    /// An operand was missing, so one of the machine's handlers supplied it and the instruction took effect.
+    Underflow,
+    /// The operation was undefined for its operands (e.g. division by zero), so one of the machine's handlers
+    /// supplied the result and the instruction took effect.
+    Undefined,
+    /// An operand was missing and couldn't be supplied, so the instruction did nothing.
+    Empty,
+    /// The state didn't have enough memory left for the result.
+    Full,
+    /// A position or index operand was out of range.
+    OutOfRange,
+    /// An integer didn't encode a valid instruction.
+    InvalidOpcode,
+    /// An operand was of the wrong kind (e.g. `Quotename` found something other than a name), so the instruction
+    /// did nothing.
+    TypeMismatch,
+}
+
+impl Fault {
+    /// Whether a handler covered for the fault, so the instruction still took effect.
+    pub fn is_handled(self) -> bool {
+        matches!(self, Fault::Underflow | Fault::Undefined)
+    }
+}
+
+impl From<SizeError> for Fault {
+    fn from(e: SizeError) -> Fault {
+        match e {
+            SizeError::Full => Fault::Full,
+            SizeError::Empty => Fault::Empty,
+            SizeError::OutOfRange => Fault::OutOfRange,
+        }
+    }
+}
+
 /// An instruction which can be executed on a `Machine`.
 pub trait Instruction<IH, IntH, FloatH>: Sized {
-    /// `operate` returns a boolean value which indicates the success of the operation.
-    fn operate(self, &mut Machine<Self, IH, IntH, FloatH>) -> (Option<Self>, bool);
+    /// `operate` returns the instruction provided to the caller of the machine, if any, and whether the operation
+    /// succeeded, or the `Fault` which prevented it from executing cleanly.
+    fn operate(self, machine: &mut Machine<Self, IH, IntH, FloatH>) -> (Option<Self>, Result<(), Fault>);
 }
 
@@ -0,0 +1,391 @@
+//! A textual assembly language for `SimpleInstruction` programs.
+//!
+//! A program is a whitespace separated sequence of instructions, where `;` starts a comment running to the end of
+//! the line:
+//!
+//! - `Addi64`, `CreateLoop`, ... are `PlainOp`s, written exactly as their variant names.
+//! - `3`, `-7` are `Pushi64` literals, `2.5`, `1e-3`, `NaN`, `inf` are `Pushf64` literals and `true`, `false` are
+//!   `Pushb` literals.
+//! - `i64[1 2 3]`, `f64[0.5 1.5]` and `b[true false]` are `Pushi64v`, `Pushf64v` and `Pushbv` literals.
+//! - `"some text"` is a `Pushs` literal and `'c'` is a `Pushc` literal. Both accept the escapes `\n`, `\r`, `\t`,
+//!   `\0`, `\\`, `\"`, `\'` and `\u{..}`.
+//! - `name:3` is a `Name`, `tag:3` is a `Tag` and `ref:3` is a `TagRef`.
+//! - `( ... )` is a `BasicBlock`, `loop( ... )` is a `Loop` and `if( ... )( ... )` is an `If` whose first block runs
+//!   when the popped bool is true.
+//! - `while( ... )` is a `While`, `times( ... )` is a `DoTimes`, `itimes( ... )` is a `DoTimes` which pushes its
+//!   index and `range( ... )` is a `DoRange`. Counted loops which have already started are written
+//!   `times@1:3( ... )` with the next index and the number of iterations, and `range@4:10( ... )` with the next
+//!   index and the end.
+
+use super::{PlainOp, SimpleInstruction};
+use vec::TrackedVec;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The reason a program failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A word was neither a literal nor the name of a `PlainOp`.
+    UnknownInstruction(String),
+    /// A word looked like a literal but could not be parsed as one.
+    InvalidLiteral(String),
+    /// A token appeared somewhere it can't be used.
+    UnexpectedToken(String),
+    /// A delimiter was opened but never closed.
+    Unclosed(char),
+    /// Something else was required at this position.
+    Expected(&'static str),
+}
+
+/// An error encountered while parsing, with the 1-based line and column where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ParseErrorKind::*;
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match self.kind {
+            UnknownInstruction(ref w) => write!(f, "unknown instruction `{}`", w),
+            InvalidLiteral(ref w) => write!(f, "invalid literal `{}`", w),
+            UnexpectedToken(ref w) => write!(f, "unexpected `{}`", w),
+            Unclosed(c) => write!(f, "unclosed `{}`", c),
+            Expected(what) => write!(f, "expected {}", what),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse a program into the sequence of instructions it contains.
+pub fn parse(src: &str) -> Result<Vec<SimpleInstruction>, ParseError> {
+    Parser::new(src)?.parse_sequence(None)
+}
+
+impl FromStr for SimpleInstruction {
+    type Err = ParseError;
+
+    /// Parse a program containing exactly one instruction.
+    fn from_str(src: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(src)?;
+        let ins = match parser.next() {
+            Some((token, pos)) => parser.parse_item(token, pos)?,
+            None => return Err(parser.error_at_end(ParseErrorKind::Expected("an instruction"))),
+        };
+        match parser.next() {
+            Some((token, pos)) => Err(error(ParseErrorKind::UnexpectedToken(token.to_string()), pos)),
+            None => Ok(ins),
+        }
+    }
+}
+
+impl FromStr for PlainOp {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, ParseError> {
+        let name = src.trim();
+        PlainOp::from_name(name).ok_or_else(|| error(ParseErrorKind::UnknownInstruction(name.to_string()), (1, 1)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Open,
+    Close,
+    OpenVec,
+    CloseVec,
+    Word(&'a str),
+    /// A quoted literal with its quote character and the raw text between the quotes.
+    Quoted(char, &'a str),
+}
+
+impl<'a> fmt::Display for Token<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::OpenVec => write!(f, "["),
+            Token::CloseVec => write!(f, "]"),
+            Token::Word(w) => write!(f, "{}", w),
+            Token::Quoted(quote, text) => write!(f, "{}{}{}", quote, text, quote),
+        }
+    }
+}
+
+/// A line and column, both starting at 1.
+type Position = (usize, usize);
+
+fn error(kind: ParseErrorKind, (line, column): Position) -> ParseError {
+    ParseError { kind, line, column }
+}
+
+fn tokenize<'a>(src: &'a str) -> Result<(Vec<(Token<'a>, Position)>, Position), ParseError> {
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    let mut word_start = None;
+    let mut comment = false;
+    // The quote, start of the text and position of the quoted literal being read, if any.
+    let mut quoted = None;
+    let mut escaped = false;
+    for (ix, c) in src.char_indices() {
+        if let Some((quote, start, pos)) = quoted {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                tokens.push((Token::Quoted(quote, &src[start..ix]), pos));
+                quoted = None;
+            }
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+            continue;
+        }
+        let delimiter = match c {
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            '[' => Some(Token::OpenVec),
+            ']' => Some(Token::CloseVec),
+            _ => None,
+        };
+        if comment || c.is_whitespace() || c == ';' || delimiter.is_some() {
+            if let Some((start, pos)) = word_start.take() {
+                tokens.push((Token::Word(&src[start..ix]), pos));
+            }
+        }
+        if c == '\n' {
+            comment = false;
+        } else if c == ';' {
+            comment = true;
+        }
+        if !comment {
+            if let Some(token) = delimiter {
+                tokens.push((token, (line, column)));
+            } else if (c == '"' || c == '\'') && word_start.is_none() {
+                quoted = Some((c, ix + 1, (line, column)));
+            } else if !c.is_whitespace() && word_start.is_none() {
+                word_start = Some((ix, (line, column)));
+            }
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    if let Some((quote, _, pos)) = quoted {
+        return Err(error(ParseErrorKind::Unclosed(quote), pos));
+    }
+    if let Some((start, pos)) = word_start {
+        tokens.push((Token::Word(&src[start..]), pos));
+    }
+    Ok((tokens, (line, column)))
+}
+
+struct Parser<'a> {
+    tokens: ::std::vec::IntoIter<(Token<'a>, Position)>,
+    end: Position,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Result<Parser<'a>, ParseError> {
+        let (tokens, end) = tokenize(src)?;
+        Ok(Parser {
+            tokens: tokens.into_iter(),
+            end,
+        })
+    }
+
+    fn next(&mut self) -> Option<(Token<'a>, Position)> {
+        self.tokens.next()
+    }
+
+    fn error_at_end(&self, kind: ParseErrorKind) -> ParseError {
+        error(kind, self.end)
+    }
+
+    fn expect(&mut self, expected: Token<'a>, what: &'static str) -> Result<Position, ParseError> {
+        match self.next() {
+            Some((token, pos)) if token == expected => Ok(pos),
+            Some((_, pos)) => Err(error(ParseErrorKind::Expected(what), pos)),
+            None => Err(self.error_at_end(ParseErrorKind::Expected(what))),
+        }
+    }
+
+    /// Parse instructions until the end of input, or until a `)` if `opened` holds the position of its `(`.
+    fn parse_sequence(&mut self, opened: Option<Position>) -> Result<Vec<SimpleInstruction>, ParseError> {
+        let mut instructions = Vec::new();
+        loop {
+            match self.next() {
+                Some((Token::Close, pos)) => {
+                    return if opened.is_some() {
+                        Ok(instructions)
+                    } else {
+                        Err(error(ParseErrorKind::UnexpectedToken(")".to_string()), pos))
+                    };
+                }
+                Some((token, pos)) => instructions.push(self.parse_item(token, pos)?),
+                None => {
+                    return match opened {
+                        Some(pos) => Err(error(ParseErrorKind::Unclosed('('), pos)),
+                        None => Ok(instructions),
+                    };
+                }
+            }
+        }
+    }
+
+    fn parse_block(&mut self) -> Result<TrackedVec<SimpleInstruction>, ParseError> {
+        let pos = self.expect(Token::Open, "`(`")?;
+        self.parse_sequence(Some(pos)).map(TrackedVec::new_from_vec)
+    }
+
+    /// Parse the elements of a vector literal up to its closing `]`.
+    fn parse_vector<T: FromStr>(&mut self) -> Result<Vec<T>, ParseError> {
+        let opened = self.expect(Token::OpenVec, "`[`")?;
+        let mut elements = Vec::new();
+        loop {
+            match self.next() {
+                Some((Token::CloseVec, _)) => return Ok(elements),
+                Some((Token::Word(w), pos)) => {
+                    elements.push(w.parse().map_err(|_| error(ParseErrorKind::InvalidLiteral(w.to_string()), pos))?)
+                }
+                Some((token, pos)) => return Err(error(ParseErrorKind::UnexpectedToken(token.to_string()), pos)),
+                None => return Err(error(ParseErrorKind::Unclosed('['), opened)),
+            }
+        }
+    }
+
+    fn parse_item(&mut self, token: Token<'a>, pos: Position) -> Result<SimpleInstruction, ParseError> {
+        use self::SimpleInstruction::*;
+        let word = match token {
+            Token::Open => {
+                return self.parse_sequence(Some(pos)).map(|v| BasicBlock(TrackedVec::new_from_vec(v).into_iter()));
+            }
+            Token::Word(w) => w,
+            Token::Quoted(quote, text) => {
+                let invalid = || error(ParseErrorKind::InvalidLiteral(token.to_string()), pos);
+                let s = unescape(text).ok_or_else(invalid)?;
+                if quote == '"' {
+                    return Ok(Pushs(s));
+                }
+                let mut chars = s.chars();
+                return match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Pushc(c)),
+                    _ => Err(invalid()),
+                };
+            }
+            _ => return Err(error(ParseErrorKind::UnexpectedToken(token.to_string()), pos)),
+        };
+        match word {
+            "loop" => self.parse_block().map(|b| Loop(b.into_cycle_iter())),
+            "while" => self.parse_block().map(While),
+            "times" => self.parse_block().map(|b| DoTimes(b, false, None)),
+            "itimes" => self.parse_block().map(|b| DoTimes(b, true, None)),
+            "range" => self.parse_block().map(|b| DoRange(b, None)),
+            _ if word.starts_with("name:") => parse_label(word, "name:", pos).map(Name),
+            _ if word.starts_with("tag:") => parse_label(word, "tag:", pos).map(Tag),
+            _ if word.starts_with("ref:") => parse_label(word, "ref:", pos).map(TagRef),
+            _ if word.starts_with("times@") || word.starts_with("itimes@") => {
+                let push_index = word.starts_with('i');
+                let state = parse_range(&word[word.find('@').unwrap() + 1..]);
+                let state = state.ok_or_else(|| error(ParseErrorKind::InvalidLiteral(word.to_string()), pos))?;
+                self.parse_block().map(|b| DoTimes(b, push_index, Some(state)))
+            }
+            _ if word.starts_with("range@") => {
+                let range = parse_range(&word["range@".len()..]);
+                let range = range.ok_or_else(|| error(ParseErrorKind::InvalidLiteral(word.to_string()), pos))?;
+                self.parse_block().map(|b| DoRange(b, Some(range)))
+            }
+            "if" => {
+                let t = self.parse_block()?;
+                let f = self.parse_block()?;
+                Ok(If(t.into_iter(), f.into_iter()))
+            }
+            "i64" => self.parse_vector().map(|v| Pushi64v(TrackedVec::new_from_vec(v))),
+            "f64" => self.parse_vector().map(|v| Pushf64v(TrackedVec::new_from_vec(v))),
+            "b" => self.parse_vector().map(|v| Pushbv(TrackedVec::new_from_vec(v))),
+            "true" => Ok(Pushb(true)),
+            "false" => Ok(Pushb(false)),
+            _ if is_numeric(word) => parse_number(word).ok_or_else(|| {
+                error(ParseErrorKind::InvalidLiteral(word.to_string()), pos)
+            }),
+            _ => {
+                super::PlainOp::from_name(word)
+                    .map(PlainOp)
+                    .ok_or_else(|| error(ParseErrorKind::UnknownInstruction(word.to_string()), pos))
+            }
+        }
+    }
+}
+
+/// Whether a word should be parsed as a number rather than an instruction name.
+fn is_numeric(word: &str) -> bool {
+    let unsigned = word.trim_start_matches(&['-', '+'][..]);
+    unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') || unsigned == "inf" || unsigned == "NaN"
+}
+
+/// Parse the number following `prefix` in a name or tag literal.
+fn parse_label(word: &str, prefix: &str, pos: Position) -> Result<u32, ParseError> {
+    word[prefix.len()..].parse().map_err(|_| error(ParseErrorKind::InvalidLiteral(word.to_string()), pos))
+}
+
+/// Parse the `ix:end` state of a running `DoRange`, or the `ix:n` state of a running `DoTimes`.
+fn parse_range(state: &str) -> Option<(i64, i64)> {
+    let mut parts = state.splitn(2, ':');
+    let ix = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+    Some((ix, end))
+}
+
+fn parse_number(word: &str) -> Option<SimpleInstruction> {
+    if word.contains(|c: char| c == '.' || c == 'e' || c == 'E' || c.is_alphabetic()) {
+        word.parse().ok().map(SimpleInstruction::Pushf64)
+    } else {
+        word.parse().ok().map(SimpleInstruction::Pushi64)
+    }
+}
+
+/// Replace the escape sequences in the text of a quoted literal, or return `None` if one is invalid.
+fn unescape(text: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                let rest = chars.as_str();
+                let end = rest.find('}')?;
+                if !rest.starts_with('{') {
+                    return None;
+                }
+                let c = u32::from_str_radix(&rest[1..end], 16).ok().and_then(::std::char::from_u32)?;
+                chars = rest[end + 1..].chars();
+                c
+            }
+            _ => return None,
+        });
+    }
+    Some(out)
+}
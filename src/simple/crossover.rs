@@ -0,0 +1,158 @@
+use super::{Chromosome, Gene};
+
+use std::cmp;
+use rand::Rng;
+use rand::distributions::{IndependentSample, Normal};
+
+/// A strategy for producing a child from two parents.
+pub trait Crossover {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome;
+}
+
+/// Build a child from `genes`, keeping the crossover points of either parent which fall inside it.
+fn child(a: &Chromosome, b: &Chromosome, genes: Vec<Gene>) -> Chromosome {
+    let crossovers = a.crossovers.iter().chain(b.crossovers.iter()).cloned().filter(|&n| n < genes.len()).collect();
+    Chromosome {
+        genes,
+        crossovers,
+    }
+}
+
+/// Copy genes from each parent in turn, switching at the next crossover point of the other parent past the last
+/// switch. This is what `Chromosome::mate` does.
+///
+/// The crossover points the switches happen at become the crossover points of the child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossoverPoints;
+
+impl Crossover for CrossoverPoints {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, _: &mut R) -> Chromosome {
+        a.mate(b)
+    }
+}
+
+/// Take the gene at each position from either parent with even odds, skipping the position if the chosen parent is
+/// too short to have one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uniform;
+
+impl Crossover for Uniform {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome {
+        let len = cmp::max(a.genes.len(), b.genes.len());
+        let genes = (0..len)
+            .filter_map(|i| if rng.gen() { a.genes.get(i) } else { b.genes.get(i) })
+            .cloned()
+            .collect();
+        child(a, b, genes)
+    }
+}
+
+/// Pick a cut point inside both parents, where one exists, and join the genes of `a` before it with the genes of `b`
+/// after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnePoint;
+
+impl Crossover for OnePoint {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome {
+        let cut = cut_points(a, b, 1, rng)[0];
+        let genes = a.genes[..cut].iter().chain(&b.genes[cut..]).cloned().collect();
+        child(a, b, genes)
+    }
+}
+
+/// Pick two cut points inside both parents, where they exist, and replace the genes of `a` between them with those of
+/// `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwoPoint;
+
+impl Crossover for TwoPoint {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome {
+        let cuts = cut_points(a, b, 2, rng);
+        let genes = a.genes[..cuts[0]]
+            .iter()
+            .chain(&b.genes[cuts[0]..cuts[1]])
+            .chain(&a.genes[cuts[1]..])
+            .cloned()
+            .collect();
+        child(a, b, genes)
+    }
+}
+
+/// Pick `count` sorted cut points from `1..len` of the shorter parent, or from `0..=len` if it is too short to cut
+/// inside of.
+fn cut_points<R: Rng>(a: &Chromosome, b: &Chromosome, count: usize, rng: &mut R) -> Vec<usize> {
+    let len = cmp::min(a.genes.len(), b.genes.len());
+    let mut cuts = if len > count {
+        let mut inside = (1..len).collect::<Vec<_>>();
+        rng.shuffle(&mut inside);
+        inside.truncate(count);
+        inside
+    } else {
+        (0..count).map(|_| rng.gen_range(0, len + 1)).collect()
+    };
+    cuts.sort();
+    cuts
+}
+
+/// Walk along one parent copying genes, switching to the same position in the other parent with a chance of `rate`
+/// after each gene, as in the alternation step of ULTRA.
+///
+/// The position moves by a Gaussian with a standard deviation of `alignment_deviation` when switching, so the
+/// parents don't need to line up exactly. The walk stops at the end of the current parent or once the child is as
+/// long as both parents together.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alternation {
+    pub rate: f64,
+    pub alignment_deviation: f64,
+}
+
+impl Crossover for Alternation {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome {
+        let max = a.genes.len() + b.genes.len();
+        let mut parents = if rng.gen() { (a, b) } else { (b, a) };
+        let mut genes = Vec::new();
+        let mut i = 0i64;
+        while let Some(gene) = parents.0.genes.get(i as usize) {
+            if genes.len() >= max {
+                break;
+            }
+            genes.push(gene.clone());
+            if rng.gen::<f64>() < self.rate {
+                parents = (parents.1, parents.0);
+                if self.alignment_deviation > 0.0 {
+                    let shift = Normal::new(0.0, self.alignment_deviation).ind_sample(rng).round() as i64;
+                    i = cmp::max(i + shift, 0);
+                }
+            }
+            i += 1;
+        }
+        child(a, b, genes)
+    }
+}
+
+/// Replace a random segment of `a` with a random segment of `b` which is at most twice as long, so the child is close
+/// in size to `a`.
+///
+/// At least one gene of `a` is kept when `a` has more than one gene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeFair;
+
+impl Crossover for SizeFair {
+    fn cross<R: Rng>(&self, a: &Chromosome, b: &Chromosome, rng: &mut R) -> Chromosome {
+        let (a_len, b_len) = (a.genes.len(), b.genes.len());
+        if a_len == 0 || b_len == 0 {
+            return child(a, b, a.genes.iter().chain(&b.genes).cloned().collect());
+        }
+        let removed = rng.gen_range(1, cmp::max(a_len, 2));
+        let start = rng.gen_range(0, a_len - removed + 1);
+        let inserted = rng.gen_range(1, cmp::min(b_len, 2 * removed) + 1);
+        let from = rng.gen_range(0, b_len - inserted + 1);
+        let genes = a.genes[..start]
+            .iter()
+            .chain(&b.genes[from..from + inserted])
+            .chain(&a.genes[start + removed..])
+            .cloned()
+            .collect();
+        child(a, b, genes)
+    }
+}
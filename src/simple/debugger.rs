@@ -0,0 +1,134 @@
+use {Fault, Machine, Stack, State};
+use super::{PlainOp, SimpleInstruction, disassemble};
+
+/// A condition which stops `Debugger::run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop just before this operation executes.
+    Op(PlainOp),
+    /// Stop when the stack grows to at least this depth.
+    Depth(Stack, usize),
+    /// Stop when the memory used by the state grows to at least this size.
+    Memory(usize),
+}
+
+/// The reason `Debugger::run` stopped.
+#[derive(Debug, Clone)]
+pub enum Stop {
+    /// The breakpoint at this index of `Debugger::breakpoints` was hit.
+    Breakpoint(usize),
+    /// An instruction was provided by the program.
+    Provided(SimpleInstruction),
+    /// The exe stack is empty.
+    Halted,
+    /// The requested number of steps were executed without stopping otherwise.
+    StepLimit,
+}
+
+/// Executes a `Machine` one instruction at a time, stopping at breakpoints.
+#[derive(Debug, Clone)]
+pub struct Debugger<IH, IntH, FloatH> {
+    pub machine: Machine<SimpleInstruction, IH, IntH, FloatH>,
+    pub breakpoints: Vec<Breakpoint>,
+    /// The number of instructions executed through the debugger.
+    pub steps: usize,
+    /// Whether `run` last stopped at an `Op` breakpoint just before the next instruction, which the next `run`
+    /// executes rather than stopping there again.
+    stopped: bool,
+}
+
+impl<IH, IntH, FloatH> Debugger<IH, IntH, FloatH>
+    where IH: FnMut() -> SimpleInstruction,
+          IntH: FnMut() -> i64,
+          FloatH: FnMut() -> f64
+{
+    pub fn new(machine: Machine<SimpleInstruction, IH, IntH, FloatH>) -> Self {
+        Debugger {
+            machine,
+            breakpoints: Vec::new(),
+            steps: 0,
+            stopped: false,
+        }
+    }
+
+    pub fn into_machine(self) -> Machine<SimpleInstruction, IH, IntH, FloatH> {
+        self.machine
+    }
+
+    pub fn state(&self) -> &State<SimpleInstruction> {
+        &self.machine.state
+    }
+
+    /// The instruction which will execute on the next step, unless the instruction handler has to produce one.
+    pub fn next_instruction(&self) -> Option<&SimpleInstruction> {
+        self.machine.state.exe_stack().last()
+    }
+
+    /// Disassemble the remainder of the program on the exe stack, starting with the next instruction to execute.
+    pub fn listing(&self) -> String {
+        let remaining = self.machine.state.exe_stack().iter().rev().cloned().collect::<Vec<_>>();
+        disassemble(&remaining)
+    }
+
+    /// Execute a single instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> (Option<SimpleInstruction>, Result<(), Fault>) {
+        self.steps += 1;
+        self.stopped = false;
+        self.machine.cycle()
+    }
+
+    /// The index of the first `Op` breakpoint on the next instruction, if any.
+    fn op_breakpoint(&self) -> Option<usize> {
+        let next = match self.next_instruction() {
+            Some(&SimpleInstruction::PlainOp(next)) => next,
+            _ => return None,
+        };
+        self.breakpoints.iter().position(|b| *b == Breakpoint::Op(next))
+    }
+
+    /// Execute up to `limit` instructions, stopping early on a breakpoint, a provided instruction or an empty exe
+    /// stack.
+    ///
+    /// `Op` breakpoints stop before the operation executes, including when it is the very next instruction. Running
+    /// again after stopping there executes the operation and carries on.
+    pub fn run(&mut self, limit: usize) -> Stop {
+        for _ in 0..limit {
+            if self.next_instruction().is_none() {
+                return Stop::Halted;
+            }
+            if !self.stopped {
+                if let Some(ix) = self.op_breakpoint() {
+                    self.stopped = true;
+                    return Stop::Breakpoint(ix);
+                }
+            }
+            let depths = self.machine.state.depths();
+            let memory = self.machine.state.size();
+            if let (Some(ins), _) = self.step() {
+                return Stop::Provided(ins);
+            }
+
+            let new_depths = self.machine.state.depths();
+            let new_memory = self.machine.state.size();
+            let hit = self.breakpoints.iter().position(|b| match *b {
+                Breakpoint::Op(_) => false,
+                Breakpoint::Depth(stack, depth) => depths.get(stack) < depth && new_depths.get(stack) >= depth,
+                Breakpoint::Memory(size) => memory < size && new_memory >= size,
+            });
+            if let Some(ix) = hit {
+                return Stop::Breakpoint(ix);
+            }
+        }
+        Stop::StepLimit
+    }
+
+    /// Execute up to `limit` instructions until `op` is about to execute, ignoring other breakpoints.
+    ///
+    /// Reaching `op` is reported as `Stop::Breakpoint(0)`.
+    pub fn run_until_op(&mut self, op: PlainOp, limit: usize) -> Stop {
+        let breakpoints = ::std::mem::replace(&mut self.breakpoints, vec![Breakpoint::Op(op)]);
+        let stop = self.run(limit);
+        self.breakpoints = breakpoints;
+        stop
+    }
+}
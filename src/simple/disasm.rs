@@ -0,0 +1,184 @@
+use super::{Chromosome, PlainOp, Plush, SimpleInstruction};
+
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Blocks whose single-line form is longer than this are split across lines when indenting.
+const LINE_WIDTH: usize = 80;
+const INDENT: &str = "    ";
+
+/// Render a program as indented assembly which `parse` turns back into the same instructions.
+///
+/// Instructions which have been partially executed are rendered as what remains of them, so disassembling the
+/// instructions on the exe stack of a running `Machine` gives a program which continues from where it left off.
+pub fn disassemble(instructions: &[SimpleInstruction]) -> String {
+    let mut out = String::new();
+    for ins in instructions {
+        write_indented(&mut out, ins, 0).unwrap();
+        out.push('\n');
+    }
+    out
+}
+
+/// The keyword and block bodies of an instruction made of nested instructions.
+fn structure(ins: &SimpleInstruction) -> Option<(String, Vec<Vec<&SimpleInstruction>>)> {
+    use self::SimpleInstruction::*;
+    match *ins {
+        BasicBlock(ref b) => Some((String::new(), vec![b.as_slice().iter().collect()])),
+        Loop(ref l) => {
+            let (next, done) = l.as_slices();
+            Some(("loop".to_string(), vec![next.iter().chain(done).collect()]))
+        }
+        If(ref t, ref f) => {
+            Some(("if".to_string(), vec![t.as_slice().iter().collect(), f.as_slice().iter().collect()]))
+        }
+        While(ref b) => Some(("while".to_string(), vec![b.as_slice().iter().collect()])),
+        DoTimes(ref b, push_index, state) => {
+            let keyword = if push_index { "itimes" } else { "times" };
+            let keyword = match state {
+                Some((ix, n)) => format!("{}@{}:{}", keyword, ix, n),
+                None => keyword.to_string(),
+            };
+            Some((keyword, vec![b.as_slice().iter().collect()]))
+        }
+        DoRange(ref b, range) => {
+            let keyword = match range {
+                Some((ix, end)) => format!("range@{}:{}", ix, end),
+                None => "range".to_string(),
+            };
+            Some((keyword, vec![b.as_slice().iter().collect()]))
+        }
+        _ => None,
+    }
+}
+
+fn write_vec<W: Write, T: fmt::Debug>(out: &mut W, prefix: &str, v: &[T]) -> fmt::Result {
+    write!(out, "{}[", prefix)?;
+    for (ix, e) in v.iter().enumerate() {
+        if ix != 0 {
+            out.write_char(' ')?;
+        }
+        write!(out, "{:?}", e)?;
+    }
+    out.write_char(']')
+}
+
+/// Write an instruction on a single line.
+fn write_inline<W: Write>(out: &mut W, ins: &SimpleInstruction) -> fmt::Result {
+    use self::SimpleInstruction::*;
+    if let Some((keyword, bodies)) = structure(ins) {
+        out.write_str(&keyword)?;
+        for body in bodies {
+            out.write_char('(')?;
+            for (ix, ins) in body.into_iter().enumerate() {
+                if ix != 0 {
+                    out.write_char(' ')?;
+                }
+                write_inline(out, ins)?;
+            }
+            out.write_char(')')?;
+        }
+        return Ok(());
+    }
+    match *ins {
+        PlainOp(ref op) => write!(out, "{}", op),
+        Pushi64(n) => write!(out, "{}", n),
+        Pushf64(n) => write!(out, "{:?}", n),
+        Pushb(b) => write!(out, "{}", b),
+        Pushi64v(ref v) => write_vec(out, "i64", v.as_slice()),
+        Pushf64v(ref v) => write_vec(out, "f64", v.as_slice()),
+        Pushs(ref s) => write!(out, "{:?}", s),
+        Pushc(c) => write!(out, "{:?}", c),
+        Pushbv(ref v) => write_vec(out, "b", v.as_slice()),
+        Name(n) => write!(out, "name:{}", n),
+        Tag(n) => write!(out, "tag:{}", n),
+        TagRef(n) => write!(out, "ref:{}", n),
+        BasicBlock(_) | Loop(_) | If(..) | DoTimes(..) | DoRange(..) | While(_) => unreachable!(),
+    }
+}
+
+/// Write an instruction starting at the current position of a line indented `depth` levels, splitting its blocks
+/// across lines if they are too long or contain other blocks.
+fn write_indented<W: Write>(out: &mut W, ins: &SimpleInstruction, depth: usize) -> fmt::Result {
+    let (keyword, bodies) = match structure(ins) {
+        Some(s) => s,
+        None => return write_inline(out, ins),
+    };
+    let mut inline = String::new();
+    write_inline(&mut inline, ins)?;
+    let nested = bodies.iter().any(|body| body.iter().any(|ins| structure(ins).is_some()));
+    if !nested && inline.len() + depth * INDENT.len() <= LINE_WIDTH {
+        return out.write_str(&inline);
+    }
+
+    let indent = |out: &mut W, depth: usize| (0..depth).try_for_each(|_| out.write_str(INDENT));
+    out.write_str(&keyword)?;
+    for body in bodies {
+        out.write_str("(\n")?;
+        // Instructions without blocks are packed onto lines, while those with blocks get lines of their own.
+        let mut line = String::new();
+        for ins in body {
+            if structure(ins).is_some() {
+                if !line.is_empty() {
+                    indent(out, depth + 1)?;
+                    writeln!(out, "{}", line)?;
+                    line.clear();
+                }
+                indent(out, depth + 1)?;
+                write_indented(out, ins, depth + 1)?;
+                out.write_char('\n')?;
+                continue;
+            }
+            let mut word = String::new();
+            write_inline(&mut word, ins)?;
+            if !line.is_empty() && line.len() + 1 + word.len() + (depth + 1) * INDENT.len() > LINE_WIDTH {
+                indent(out, depth + 1)?;
+                writeln!(out, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        if !line.is_empty() {
+            indent(out, depth + 1)?;
+            writeln!(out, "{}", line)?;
+        }
+        indent(out, depth)?;
+        out.write_char(')')?;
+    }
+    Ok(())
+}
+
+impl Display for PlainOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `{}` renders the instruction on one line and `{:#}` renders it indented across several lines.
+impl Display for SimpleInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if f.alternate() {
+            write_indented(f, self, 0)
+        } else {
+            write_inline(f, self)
+        }
+    }
+}
+
+/// Renders the genes as the `BasicBlock` the chromosome converts into.
+impl Display for Chromosome {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let ins: SimpleInstruction = self.into();
+        Display::fmt(&ins, f)
+    }
+}
+
+/// Renders the genes as the `BasicBlock` the genome translates into.
+impl Display for Plush {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let ins: SimpleInstruction = self.into();
+        Display::fmt(&ins, f)
+    }
+}
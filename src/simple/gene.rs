@@ -0,0 +1,203 @@
+use super::{PlainOp, SimpleInstruction};
+use vec::TrackedVec;
+
+use std::mem;
+use rand::Rng;
+use rand::distributions::{IndependentSample, Normal};
+
+/// The kind of block an `Open` gene starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Block {
+    Basic,
+    Loop,
+    While,
+    DoTimes,
+    /// A `DoTimes` which pushes the index of each iteration.
+    DoTimesIndexed,
+    DoRange,
+    /// Takes two bodies, the one run when the popped bool is true followed by the one run when it is false.
+    If,
+}
+
+impl Block {
+    /// The number of bodies the block is made of, each of which is ended by a close.
+    pub fn bodies(self) -> usize {
+        match self {
+            Block::If => 2,
+            _ => 1,
+        }
+    }
+
+    /// Wrap `bodies` in the instruction for this kind of block. Missing bodies are empty and extra ones are ignored.
+    pub fn wrap(self, bodies: Vec<Vec<SimpleInstruction>>) -> SimpleInstruction {
+        let mut bodies = bodies.into_iter().map(TrackedVec::new_from_vec);
+        let mut body = || bodies.next().unwrap_or_default();
+        match self {
+            Block::Basic => SimpleInstruction::BasicBlock(body().into_iter()),
+            Block::Loop => SimpleInstruction::Loop(body().into_cycle_iter()),
+            Block::While => SimpleInstruction::While(body()),
+            Block::DoTimes => SimpleInstruction::DoTimes(body(), false, None),
+            Block::DoTimesIndexed => SimpleInstruction::DoTimes(body(), true, None),
+            Block::DoRange => SimpleInstruction::DoRange(body(), None),
+            Block::If => {
+                let t = body();
+                SimpleInstruction::If(t.into_iter(), body().into_iter())
+            }
+        }
+    }
+}
+
+/// Assembles instructions into nested blocks as the blocks are opened and closed.
+///
+/// A close without an open block is ignored, and `finish` closes any blocks which are still open.
+#[derive(Debug, Default)]
+pub struct BlockBuilder {
+    /// Each open block, the bodies of it which were already closed and the body it was opened in.
+    open: Vec<(Block, Vec<Vec<SimpleInstruction>>, Vec<SimpleInstruction>)>,
+    body: Vec<SimpleInstruction>,
+}
+
+impl BlockBuilder {
+    pub fn new() -> BlockBuilder {
+        BlockBuilder::default()
+    }
+
+    pub fn push(&mut self, ins: SimpleInstruction) {
+        self.body.push(ins);
+    }
+
+    pub fn open(&mut self, block: Block) {
+        let outer = mem::take(&mut self.body);
+        self.open.push((block, Vec::new(), outer));
+    }
+
+    /// End the current body of the innermost open block, completing the block if that was its last body. Returns
+    /// false if no block was open.
+    pub fn close(&mut self) -> bool {
+        let complete = match self.open.last_mut() {
+            Some(&mut (block, ref mut bodies, _)) => {
+                bodies.push(mem::take(&mut self.body));
+                bodies.len() >= block.bodies()
+            }
+            None => return false,
+        };
+        if complete {
+            let (block, bodies, outer) = self.open.pop().unwrap();
+            self.body = outer;
+            self.body.push(block.wrap(bodies));
+        }
+        true
+    }
+
+    /// Open, close or push the instruction for `gene`.
+    pub fn add(&mut self, gene: &Gene) {
+        match *gene {
+            Gene::Open(block) => self.open(block),
+            Gene::Close => {
+                self.close();
+            }
+            ref gene => self.body.extend(gene.instruction()),
+        }
+    }
+
+    /// Close every open block and return everything as a `BasicBlock`.
+    pub fn finish(mut self) -> SimpleInstruction {
+        while self.close() {}
+        Block::Basic.wrap(vec![self.body])
+    }
+}
+
+/// A single gene of a `Chromosome`.
+///
+/// `Open` starts a nested block and each `Close` ends one of its bodies, as done by `BlockBuilder`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Gene {
+    Op(PlainOp),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    IntVec(Vec<i64>),
+    FloatVec(Vec<f64>),
+    BoolVec(Vec<bool>),
+    Tag(u32),
+    TagRef(u32),
+    Open(Block),
+    Close,
+}
+
+impl Gene {
+    /// The instruction the gene converts into, which is `None` for `Open` and `Close`.
+    pub fn instruction(&self) -> Option<SimpleInstruction> {
+        Some(match *self {
+            Gene::Op(op) => SimpleInstruction::PlainOp(op),
+            Gene::Int(n) => SimpleInstruction::Pushi64(n),
+            Gene::Float(n) => SimpleInstruction::Pushf64(n),
+            Gene::Bool(b) => SimpleInstruction::Pushb(b),
+            Gene::IntVec(ref v) => SimpleInstruction::Pushi64v(TrackedVec::new_from_vec(v.clone())),
+            Gene::FloatVec(ref v) => SimpleInstruction::Pushf64v(TrackedVec::new_from_vec(v.clone())),
+            Gene::BoolVec(ref v) => SimpleInstruction::Pushbv(TrackedVec::new_from_vec(v.clone())),
+            Gene::Tag(n) => SimpleInstruction::Tag(n),
+            Gene::TagRef(n) => SimpleInstruction::TagRef(n),
+            Gene::Open(_) | Gene::Close => return None,
+        })
+    }
+
+    /// Nudge the constant held by the gene.
+    ///
+    /// Ints and tags move by up to `int_delta` either way, floats by a Gaussian with a standard deviation of
+    /// `float_deviation` and bools are flipped. Vectors have one of their elements changed the same way. Genes without
+    /// a constant are left alone. A negative `int_delta` or `float_deviation` is treated as 0, so nothing moves.
+    pub fn perturb<R: Rng>(&mut self, int_delta: i64, float_deviation: f64, rng: &mut R) {
+        fn int<R: Rng>(n: &mut i64, delta: i64, rng: &mut R) {
+            // Leave room for the exclusive upper bound.
+            let delta = delta.clamp(0, i64::MAX - 1);
+            *n = n.wrapping_add(rng.gen_range(-delta, delta + 1));
+        }
+        fn float<R: Rng>(n: &mut f64, deviation: f64, rng: &mut R) {
+            if deviation > 0.0 {
+                *n += Normal::new(0.0, deviation).ind_sample(rng);
+            }
+        }
+        fn element<'a, T, R: Rng>(v: &'a mut [T], rng: &mut R) -> Option<&'a mut T> {
+            if v.is_empty() {
+                None
+            } else {
+                let ix = rng.gen_range(0, v.len());
+                v.get_mut(ix)
+            }
+        }
+
+        match *self {
+            Gene::Int(ref mut n) => int(n, int_delta, rng),
+            Gene::Float(ref mut n) => float(n, float_deviation, rng),
+            Gene::Bool(ref mut b) => *b = !*b,
+            Gene::IntVec(ref mut v) => {
+                if let Some(n) = element(v, rng) {
+                    int(n, int_delta, rng);
+                }
+            }
+            Gene::FloatVec(ref mut v) => {
+                if let Some(n) = element(v, rng) {
+                    float(n, float_deviation, rng);
+                }
+            }
+            Gene::BoolVec(ref mut v) => {
+                if let Some(b) = element(v, rng) {
+                    *b = !*b;
+                }
+            }
+            Gene::Tag(ref mut n) | Gene::TagRef(ref mut n) => {
+                let mut tag = i64::from(*n);
+                int(&mut tag, int_delta, rng);
+                *n = tag.max(0).min(i64::from(u32::MAX)) as u32;
+            }
+            Gene::Op(_) | Gene::Open(_) | Gene::Close => {}
+        }
+    }
+}
+
+impl From<PlainOp> for Gene {
+    fn from(op: PlainOp) -> Gene {
+        Gene::Op(op)
+    }
+}
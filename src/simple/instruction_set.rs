@@ -0,0 +1,246 @@
+use Stack;
+use super::{Block, Category, Gene, PlainOp, SimpleInstruction};
+
+use rand::Rng;
+use rand::distributions::range::SampleRange;
+
+/// A generator for random literal genes.
+///
+/// Ranges are half-open, so `Int { low: 0, high: 10 }` produces `Gene::Int` with a value from 0 to 9. An empty range,
+/// where `high` isn't above `low`, always produces `low`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    /// `Gene::Int` with a value in `low..high`.
+    Int { low: i64, high: i64 },
+    /// `Gene::Float` with a value in `low..high`.
+    Float { low: f64, high: f64 },
+    /// `Gene::Bool` with either value.
+    Bool,
+    /// `Gene::IntVec` with up to `max_len` values in `low..high`.
+    IntVec { low: i64, high: i64, max_len: usize },
+    /// `Gene::FloatVec` with up to `max_len` values in `low..high`.
+    FloatVec { low: f64, high: f64, max_len: usize },
+    /// `Gene::BoolVec` with up to `max_len` values.
+    BoolVec { max_len: usize },
+    /// `Gene::Tag` with a value in `low..high`.
+    Tag { low: u32, high: u32 },
+    /// `Gene::TagRef` with a value in `low..high`.
+    TagRef { low: u32, high: u32 },
+}
+
+impl Literal {
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Gene {
+        match *self {
+            Literal::Int { low, high } => Gene::Int(range(low, high, rng)),
+            Literal::Float { low, high } => Gene::Float(range(low, high, rng)),
+            Literal::Bool => Gene::Bool(rng.gen()),
+            Literal::IntVec { low, high, max_len } => {
+                let len = rng.gen_range(0, max_len.saturating_add(1));
+                Gene::IntVec((0..len).map(|_| range(low, high, rng)).collect())
+            }
+            Literal::FloatVec { low, high, max_len } => {
+                let len = rng.gen_range(0, max_len.saturating_add(1));
+                Gene::FloatVec((0..len).map(|_| range(low, high, rng)).collect())
+            }
+            Literal::BoolVec { max_len } => {
+                let len = rng.gen_range(0, max_len.saturating_add(1));
+                Gene::BoolVec(rng.gen_iter().take(len).collect())
+            }
+            Literal::Tag { low, high } => Gene::Tag(range(low, high, rng)),
+            Literal::TagRef { low, high } => Gene::TagRef(range(low, high, rng)),
+        }
+    }
+}
+
+/// Draw a value from `low..high`, or `low` if the range is empty.
+fn range<T: PartialOrd + SampleRange, R: Rng>(low: T, high: T, rng: &mut R) -> T {
+    if low < high {
+        rng.gen_range(low, high)
+    } else {
+        low
+    }
+}
+
+/// Draw an item with a probability proportional to its weight, treating negative weights as 0.
+fn choose<'a, T, R: Rng>(items: &'a [(T, f64)], rng: &mut R) -> Option<&'a T> {
+    let total = total(items);
+    if total <= 0.0 {
+        return None;
+    }
+    let mut choice = rng.gen_range(0.0, total);
+    for &(ref item, w) in items {
+        let w = w.max(0.0);
+        if choice < w {
+            return Some(item);
+        }
+        choice -= w;
+    }
+    // Rounding can leave the choice just past the last weight.
+    items.iter().rev().find(|&&(_, w)| w > 0.0).map(|(item, _)| item)
+}
+
+fn total<T>(items: &[(T, f64)]) -> f64 {
+    items.iter().map(|&(_, w)| w.max(0.0)).sum()
+}
+
+/// The operations, literals and blocks random genes are drawn from, each with a weight giving how likely it is to be
+/// drawn relative to the others, along with how far constants move when they are perturbed.
+///
+/// This is used to generate and mutate `Chromosome`s, and `handler` produces an instruction handler for a `Machine`.
+/// The default set contains every operation with a weight of 1 and no literals or blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionSet {
+    ops: Vec<(PlainOp, f64)>,
+    literals: Vec<(Literal, f64)>,
+    blocks: Vec<(Block, f64)>,
+    /// The furthest an int moves when it is perturbed.
+    pub int_delta: i64,
+    /// The standard deviation of the change to a float when it is perturbed.
+    pub float_deviation: f64,
+}
+
+impl InstructionSet {
+    /// A set with nothing to draw, to be filled in with the `with_*` methods.
+    pub fn empty() -> InstructionSet {
+        InstructionSet {
+            ops: Vec::new(),
+            literals: Vec::new(),
+            blocks: Vec::new(),
+            int_delta: 2,
+            float_deviation: 0.1,
+        }
+    }
+
+    /// Every operation with a weight of 1.
+    pub fn all() -> InstructionSet {
+        InstructionSet {
+            ops: PlainOp::ALL.iter().map(|&op| (op, 1.0)).collect(),
+            ..InstructionSet::empty()
+        }
+    }
+
+    /// Add `op` with `weight`, or change its weight if it is already in the set.
+    pub fn with_op(mut self, op: PlainOp, weight: f64) -> InstructionSet {
+        match self.ops.iter().position(|&(o, _)| o == op) {
+            Some(ix) => self.ops[ix].1 = weight,
+            None => self.ops.push((op, weight)),
+        }
+        self
+    }
+
+    /// Add every operation in `category` with `weight`, or change their weights if they are already in the set.
+    pub fn with_category(self, category: Category, weight: f64) -> InstructionSet {
+        PlainOp::ALL
+            .iter()
+            .filter(|op| op.category() == category)
+            .fold(self, |set, &op| set.with_op(op, weight))
+    }
+
+    /// Add a generator for literals with `weight`.
+    pub fn with_literal(mut self, literal: Literal, weight: f64) -> InstructionSet {
+        self.literals.push((literal, weight));
+        self
+    }
+
+    /// Add `Open(block)` genes with `weight`. `Close` genes are drawn as often as all of the `Open` genes together.
+    pub fn with_block(mut self, block: Block, weight: f64) -> InstructionSet {
+        match self.blocks.iter().position(|&(b, _)| b == block) {
+            Some(ix) => self.blocks[ix].1 = weight,
+            None => self.blocks.push((block, weight)),
+        }
+        self
+    }
+
+    /// Set how far constants move when they are perturbed.
+    pub fn with_perturbation(mut self, int_delta: i64, float_deviation: f64) -> InstructionSet {
+        self.int_delta = int_delta;
+        self.float_deviation = float_deviation;
+        self
+    }
+
+    pub fn without_op(mut self, op: PlainOp) -> InstructionSet {
+        self.ops.retain(|&(o, _)| o != op);
+        self
+    }
+
+    pub fn without_category(mut self, category: Category) -> InstructionSet {
+        self.ops.retain(|&(o, _)| o.category() != category);
+        self
+    }
+
+    /// Remove every operation which pops from or pushes to `stack`.
+    pub fn without_stack(mut self, stack: Stack) -> InstructionSet {
+        self.ops.retain(|&(o, _)| !o.pops().contains(&stack) && !o.pushes().contains(&stack));
+        self
+    }
+
+    /// The weight of `op`, which is 0 if it isn't in the set.
+    pub fn weight(&self, op: PlainOp) -> f64 {
+        self.ops.iter().find(|&&(o, _)| o == op).map(|&(_, w)| w).unwrap_or(0.0)
+    }
+
+    pub fn ops(&self) -> &[(PlainOp, f64)] {
+        &self.ops
+    }
+
+    pub fn literals(&self) -> &[(Literal, f64)] {
+        &self.literals
+    }
+
+    pub fn blocks(&self) -> &[(Block, f64)] {
+        &self.blocks
+    }
+
+    /// Draw an operation, ignoring the literals and blocks.
+    ///
+    /// Panics if no operation has a positive weight.
+    pub fn gen_op<R: Rng>(&self, rng: &mut R) -> PlainOp {
+        *choose(&self.ops, rng)
+            .unwrap_or_else(|| panic!("Error: Tried to draw an operation from an instruction set without any."))
+    }
+
+    /// Draw a gene from the operations, literals and blocks together.
+    ///
+    /// Panics if nothing has a positive weight.
+    pub fn gen_gene<R: Rng>(&self, rng: &mut R) -> Gene {
+        let kinds = [(0, total(&self.ops)),
+                     (1, total(&self.literals)),
+                     (2, total(&self.blocks)),
+                     (3, total(&self.blocks))];
+        match choose(&kinds, rng) {
+            Some(&0) => Gene::Op(self.gen_op(rng)),
+            Some(&1) => choose(&self.literals, rng).unwrap().generate(rng),
+            Some(&2) => Gene::Open(*choose(&self.blocks, rng).unwrap()),
+            Some(_) => Gene::Close,
+            None => panic!("Error: Tried to draw a gene from an empty instruction set."),
+        }
+    }
+
+    /// Draw an instruction from the operations and literals together.
+    ///
+    /// Panics if neither has a positive weight.
+    pub fn gen_instruction<R: Rng>(&self, rng: &mut R) -> SimpleInstruction {
+        let kinds = [(0, total(&self.ops)), (1, total(&self.literals))];
+        match choose(&kinds, rng) {
+            Some(&0) => SimpleInstruction::PlainOp(self.gen_op(rng)),
+            Some(_) => choose(&self.literals, rng).unwrap().generate(rng).instruction().unwrap(),
+            None => panic!("Error: Tried to draw an instruction from an empty instruction set."),
+        }
+    }
+
+    /// Perturb the constant held by `gene` as described by `Gene::perturb`.
+    pub fn perturb<R: Rng>(&self, gene: &mut Gene, rng: &mut R) {
+        gene.perturb(self.int_delta, self.float_deviation, rng);
+    }
+
+    /// An instruction handler for a `Machine` which draws instructions from the set with `rng`.
+    pub fn handler<'a, R: Rng + 'a>(&'a self, mut rng: R) -> impl FnMut() -> SimpleInstruction + 'a {
+        move || self.gen_instruction(&mut rng)
+    }
+}
+
+impl Default for InstructionSet {
+    fn default() -> InstructionSet {
+        InstructionSet::all()
+    }
+}
@@ -1,37 +1,69 @@
+mod op_table;
 mod simple_instruction;
+mod population;
+mod selection;
+mod instruction_set;
+mod gene;
+mod crossover;
+mod plush;
+mod asm;
+mod disasm;
+mod op_counter;
+mod debugger;
+pub use self::op_table::*;
 pub use self::simple_instruction::*;
+pub use self::population::*;
+pub use self::selection::*;
+pub use self::instruction_set::*;
+pub use self::gene::*;
+pub use self::crossover::*;
+pub use self::plush::*;
+pub use self::asm::*;
+pub use self::disasm::*;
+pub use self::op_counter::*;
+pub use self::debugger::*;
 
 use std::collections::BTreeSet;
-use vec;
 
 use rand::Rng;
 use rand::distributions::{Exp, IndependentSample};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Chromosome {
-    genes: Vec<PlainOp>,
+    genes: Vec<Gene>,
     crossovers: BTreeSet<usize>,
 }
 
 impl Chromosome {
-    pub fn new_rand<R: Rng>(rng: &mut R, len: usize, crossovers: usize) -> Chromosome {
+    /// Create a chromosome from `genes`, ignoring any crossover points past the end of them.
+    pub fn new(genes: Vec<Gene>, crossovers: BTreeSet<usize>) -> Chromosome {
+        let len = genes.len();
         Chromosome {
-            genes: rng.gen_iter().take(len).collect(),
+            genes,
+            crossovers: crossovers.into_iter().filter(|&n| n < len).collect(),
+        }
+    }
+
+    /// Create a chromosome of `len` genes drawn from `instructions` with up to `crossovers` crossover points.
+    pub fn new_rand<R: Rng>(rng: &mut R, instructions: &InstructionSet, len: usize, crossovers: usize) -> Chromosome {
+        Chromosome {
+            genes: (0..len).map(|_| instructions.gen_gene(rng)).collect(),
             crossovers: (0..crossovers).map(|_| rng.gen_range(0, len)).collect(),
         }
     }
 
-    pub fn mutate<R: Rng>(&mut self, maximum: usize, exp: &Exp, rng: &mut R) {
+    /// Apply up to `maximum` mutations spaced according to `exp`, drawing any new genes from `instructions`.
+    pub fn mutate<R: Rng>(&mut self, maximum: usize, exp: &Exp, instructions: &InstructionSet, rng: &mut R) {
         let mut index = 0;
         for _ in 0..maximum {
             index += exp.ind_sample(rng) as usize;
             if index >= self.genes.len() {
                 return;
             }
-            // Determine if we want to insert, remove, mutate, add crossover, or remove crossover.
-            match rng.gen_range(0, 5usize) {
+            // Determine if we want to insert, remove, mutate, perturb, add crossover, or remove crossover.
+            match rng.gen_range(0, 6usize) {
                 0 => {
-                    self.genes.insert(index, rng.gen());
+                    self.genes.insert(index, instructions.gen_gene(rng));
                 }
                 1 => {
                     self.genes.remove(index);
@@ -44,9 +76,12 @@ impl Chromosome {
                     }
                 }
                 2 => {
-                    self.genes[index] = rng.gen();
+                    self.genes[index] = instructions.gen_gene(rng);
                 }
                 3 => {
+                    instructions.perturb(&mut self.genes[index], rng);
+                }
+                4 => {
                     self.crossovers.insert(index);
                 }
                 _ => {
@@ -61,10 +96,16 @@ impl Chromosome {
         }
     }
 
+    /// Copy genes from each parent in turn, switching at the next crossover point of the other parent past the last
+    /// switch.
     pub fn mate(&self, other: &Self) -> Self {
         use std::iter::once;
-        let mut its = (self.crossovers.iter().cloned().chain(once(self.genes.len())),
-                       other.crossovers.iter().cloned().chain(once(other.genes.len())));
+        // Points outside of a chromosome can't be switched at.
+        fn points<'a>(c: &'a Chromosome) -> impl Iterator<Item = usize> + 'a {
+            let len = c.genes.len();
+            c.crossovers.iter().cloned().filter(move |&n| n < len).chain(once(len))
+        }
+        let mut its = (points(self), points(other));
         let mut genes = Vec::new();
         let mut crossovers = BTreeSet::new();
         let mut prev = 0;
@@ -82,13 +123,16 @@ impl Chromosome {
             // Work on the second chromosome.
             let next = its.1.find(|&n| n > prev);
             if let Some(next) = next {
-                genes.extend_from_slice(&self.genes[prev..next]);
+                genes.extend_from_slice(&other.genes[prev..next]);
                 crossovers.insert(next);
                 prev = next;
             } else {
                 break;
             }
         }
+        // The end of a parent isn't a crossover point of the child.
+        let len = genes.len();
+        crossovers.retain(|&n| n < len);
         Chromosome {
             genes: genes,
             crossovers: crossovers,
@@ -102,14 +146,23 @@ impl Chromosome {
     pub fn crossover_len(&self) -> usize {
         self.crossovers.len()
     }
-}
 
-impl<'a> Into<SimpleInstruction> for &'a Chromosome {
-    fn into(self) -> SimpleInstruction {
-        SimpleInstruction::BasicBlock(
-            vec::TrackedVec::new_from_vec(
-                self.genes.iter().cloned().map(SimpleInstruction::PlainOp).collect()).into_iter())
+    pub fn genes(&self) -> &[Gene] {
+        &self.genes
     }
-}
 
+    pub fn crossovers(&self) -> &BTreeSet<usize> {
+        &self.crossovers
+    }
+}
 
+/// Converts the genes into a `BasicBlock`, with the blocks opened and closed by the genes nested inside of it.
+impl From<&Chromosome> for SimpleInstruction {
+    fn from(chromosome: &Chromosome) -> SimpleInstruction {
+        let mut builder = BlockBuilder::new();
+        for gene in &chromosome.genes {
+            builder.add(gene);
+        }
+        builder.finish()
+    }
+}
@@ -0,0 +1,40 @@
+use {Fault, StackDepths, Tracer};
+use super::{PlainOp, SimpleInstruction};
+
+use std::collections::BTreeMap;
+
+/// A `Tracer` which counts how often each `PlainOp` is executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpCounter {
+    pub ops: BTreeMap<PlainOp, usize>,
+    /// The number of other instructions (literals and blocks) executed.
+    pub other: usize,
+    /// The number of instructions which raised each kind of fault.
+    pub faults: BTreeMap<Fault, usize>,
+}
+
+impl OpCounter {
+    pub fn new() -> OpCounter {
+        OpCounter::default()
+    }
+
+    /// The total number of instructions executed.
+    pub fn total(&self) -> usize {
+        self.ops.values().sum::<usize>() + self.other
+    }
+}
+
+impl Tracer<SimpleInstruction> for OpCounter {
+    fn before(&mut self, ins: &SimpleInstruction, _: usize, _: &StackDepths) {
+        match *ins {
+            SimpleInstruction::PlainOp(op) => *self.ops.entry(op).or_insert(0) += 1,
+            _ => self.other += 1,
+        }
+    }
+
+    fn after(&mut self, result: Result<(), Fault>, _: Option<&SimpleInstruction>, _: usize, _: &StackDepths) {
+        if let Err(fault) = result {
+            *self.faults.entry(fault).or_insert(0) += 1;
+        }
+    }
+}
@@ -0,0 +1,984 @@
+use Stack;
+use heapsize::HeapSizeOf;
+use rand;
+
+/// The kind of work a `PlainOp` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Category {
+    Integer,
+    Float,
+    Bool,
+    Conversion,
+    Stack,
+    Vector,
+    HigherOrder,
+    Auxiliary,
+    Construction,
+    Control,
+    Communication,
+    Input,
+    String,
+    Char,
+    Name,
+}
+
+/// Defines `PlainOp` along with its table of codes, names, categories and stack effects.
+///
+/// Each entry lists the category of the operation, the stacks it pops from (in the order it pops them) and the
+/// stacks it pushes to (in the order it pushes them). Items which are popped and pushed back, or changed in place like
+/// the vec under `Pushvi64`, count as both. Items which are only read, like the vec under `Readvi64`, are left out,
+/// except that the stack operations count the item they copy, like the top of `Dupi64`, as popped and pushed back.
+/// Only the fixed operands are listed, so the variable number of items moved by the likes of `Rotins` or `Flushi64`
+/// are left out too.
+macro_rules! plain_ops {
+    ($($(#[$attr:meta])* $op:ident: $category:ident, [$($pop:ident),*] -> [$($push:ident),*],)*) => {
+        /// Instructions which have implicit parameters and are encodable with a single integer.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+        pub enum PlainOp {
+            $($(#[$attr])* $op,)*
+        }
+
+        impl PlainOp {
+            /// Every operation, in the order of their codes.
+            pub const ALL: &'static [PlainOp] = &[$(PlainOp::$op),*];
+
+            /// The name of the variant, as used by the assembler.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(PlainOp::$op => stringify!($op),)*
+                }
+            }
+
+            /// Look up an operation by the name of its variant.
+            pub fn from_name(name: &str) -> Option<PlainOp> {
+                match name {
+                    $(stringify!($op) => Some(PlainOp::$op),)*
+                    _ => None,
+                }
+            }
+
+            pub fn category(self) -> Category {
+                match self {
+                    $(PlainOp::$op => Category::$category,)*
+                }
+            }
+
+            /// The stacks an item is popped from, in the order the operands are popped.
+            pub fn pops(self) -> &'static [Stack] {
+                match self {
+                    $(PlainOp::$op => &[$(Stack::$pop),*],)*
+                }
+            }
+
+            /// The stacks an item is pushed to, in the order the results are pushed.
+            pub fn pushes(self) -> &'static [Stack] {
+                match self {
+                    $(PlainOp::$op => &[$(Stack::$push),*],)*
+                }
+            }
+        }
+    };
+}
+
+plain_ops! {
+    // Integer operations
+    /// integer: (a -- a++)
+    Inci64: Integer, [Int] -> [Int],
+    /// integer: (a -- a--)
+    Deci64: Integer, [Int] -> [Int],
+    /// integer: (a b -- (a + b))
+    Addi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a - b))
+    Subi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a * b))
+    Muli64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a / b))
+    Divi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a % b))
+    Remi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a -- -a)
+    Negi64: Integer, [Int] -> [Int],
+    /// integer: (a -- |a|)
+    ///
+    /// `|i64::MIN|` overflows, so the int handler supplies it instead.
+    Absi64: Integer, [Int] -> [Int],
+    /// integer: (a b -- a**|b|)
+    ///
+    /// The exponent is masked to 31 bits. If the result overflows, the int handler supplies it instead.
+    Powi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a << b))
+    Rotli64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a >> b))
+    Rotri64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a << b))
+    Shftli64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a >> b))
+    Shftri64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a & b))
+    Andi64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a | b))
+    Ori64: Integer, [Int, Int] -> [Int],
+    /// integer: (a b -- (a ^ b))
+    Xori64: Integer, [Int, Int] -> [Int],
+    /// integer: (a -- ~a)
+    Invi64: Integer, [Int] -> [Int],
+    /// integer: (a b -- )
+    /// bool: ( -- a < b)
+    Lesi64: Integer, [Int, Int] -> [Bool],
+    /// integer: (a b -- )
+    /// bool: ( -- a > b)
+    Grti64: Integer, [Int, Int] -> [Bool],
+    /// integer: (a b -- )
+    /// bool: ( -- a = b)
+    Eqi64: Integer, [Int, Int] -> [Bool],
+    /// integer: (a b -- )
+    /// bool: ( -- a != b)
+    Neqi64: Integer, [Int, Int] -> [Bool],
+
+    // Floating point operations
+    /// float: (a -- a++)
+    Incf64: Float, [Float] -> [Float],
+    /// float: (a -- a--)
+    Decf64: Float, [Float] -> [Float],
+    /// float: (a b -- (a + b))
+    Addf64: Float, [Float, Float] -> [Float],
+    /// float: (a b -- (a - b))
+    Subf64: Float, [Float, Float] -> [Float],
+    /// float: (a b -- (a * b))
+    Mulf64: Float, [Float, Float] -> [Float],
+    /// float: (a b -- (a / b))
+    Divf64: Float, [Float, Float] -> [Float],
+    /// float: (a b -- (a % b))
+    Remf64: Float, [Float, Float] -> [Float],
+    /// float: (a -- -a)
+    Negf64: Float, [Float] -> [Float],
+    /// float: (a -- |a|)
+    Absf64: Float, [Float] -> [Float],
+    /// float: (a -- a**b)
+    /// int: (b -- )
+    Powif64: Float, [Int, Float] -> [Float],
+    /// float: (a b -- a**b)
+    Powff64: Float, [Float, Float] -> [Float],
+    /// float: (a b -- )
+    /// bool: ( -- a < b)
+    Lesf64: Float, [Float, Float] -> [Bool],
+    /// float: (a b -- )
+    /// bool: ( -- a > b)
+    Grtf64: Float, [Float, Float] -> [Bool],
+    /// float: (a b -- )
+    /// bool: ( -- a = b)
+    Eqf64: Float, [Float, Float] -> [Bool],
+    /// float: (a b -- )
+    /// bool: ( -- a != b)
+    Neqf64: Float, [Float, Float] -> [Bool],
+
+    // Boolean operations
+    /// bool: (a b -- a && b)
+    Andb: Bool, [Bool, Bool] -> [Bool],
+    /// bool: (a b -- a || b)
+    Orb: Bool, [Bool, Bool] -> [Bool],
+    /// bool: (a b -- a == b)
+    Eqb: Bool, [Bool, Bool] -> [Bool],
+    /// bool: (a b -- a != b)
+    Neqb: Bool, [Bool, Bool] -> [Bool],
+    /// bool: (a -- !a)
+    Notb: Bool, [Bool] -> [Bool],
+
+    // Conversion operations
+    /// int: (a -- )
+    /// float: ( -- a)
+    Itof: Conversion, [Int] -> [Float],
+    /// float: (a -- )
+    /// int: ( -- a)
+    Ftoi: Conversion, [Float] -> [Int],
+
+    // Stack manipulation
+    /// int: (b -- )
+    /// ins: (a b.. -- b.. a)
+    Rotins: Stack, [Int, Ins] -> [Ins],
+    /// int: (a b.. b -- b.. a)
+    Roti64: Stack, [Int, Int] -> [Int],
+    /// int: (b -- )
+    /// float: (a b.. -- b.. a)
+    Rotf64: Stack, [Int, Float] -> [Float],
+    /// int: (b -- )
+    /// bool: (a b.. -- b.. a)
+    Rotb: Stack, [Int, Bool] -> [Bool],
+    /// int: (b -- )
+    /// ins vec: (a b.. -- b.. a)
+    Rotinsv: Stack, [Int, InsVec] -> [InsVec],
+    /// int: (b -- )
+    /// int vec: (a b.. -- b.. a)
+    Roti64v: Stack, [Int, IntVec] -> [IntVec],
+    /// int: (b -- )
+    /// float vec: (a b.. -- b.. a)
+    Rotf64v: Stack, [Int, FloatVec] -> [FloatVec],
+    /// int: (b -- )
+    /// ins: (a b.. -- a b.. a)
+    Copyins: Stack, [Int, Ins] -> [Ins, Ins],
+    /// int: (a b.. b -- a b.. a)
+    Copyi64: Stack, [Int, Int] -> [Int, Int],
+    /// int: (b -- )
+    /// float: (a b.. -- a b.. a)
+    Copyf64: Stack, [Int, Float] -> [Float, Float],
+    /// int: (b -- )
+    /// bool: (a b.. -- a b.. a)
+    Copyb: Stack, [Int, Bool] -> [Bool, Bool],
+    /// int: (b -- )
+    /// ins vec: (a b.. -- a b.. a)
+    Copyinsv: Stack, [Int, InsVec] -> [InsVec, InsVec],
+    /// int: (b -- )
+    /// int vec: (a b.. -- a b.. a)
+    Copyi64v: Stack, [Int, IntVec] -> [IntVec, IntVec],
+    /// int: (b -- )
+    /// float vec: (a b.. -- a b.. a)
+    Copyf64v: Stack, [Int, FloatVec] -> [FloatVec, FloatVec],
+    /// ins: (a -- )
+    Popins: Stack, [Ins] -> [],
+    /// int: (a -- )
+    Popi64: Stack, [Int] -> [],
+    /// float: (a -- )
+    Popf64: Stack, [Float] -> [],
+    /// bool: (a -- )
+    Popb: Stack, [Bool] -> [],
+    /// ins vec: (a -- )
+    Popinsv: Stack, [InsVec] -> [],
+    /// int vec: (a -- )
+    Popi64v: Stack, [IntVec] -> [],
+    /// float vec: (a -- )
+    Popf64v: Stack, [FloatVec] -> [],
+
+    // Vector Operations
+    /// ins: (a -- )
+    /// ins vec: (v -- a:v)
+    Pushvins: Vector, [Ins, InsVec] -> [InsVec],
+    /// int: (a -- )
+    /// int vec: (v -- a:v)
+    Pushvi64: Vector, [Int, IntVec] -> [IntVec],
+    /// float: (a -- )
+    /// float vec: (v -- a:v)
+    Pushvf64: Vector, [Float, FloatVec] -> [FloatVec],
+    /// ins vec: (_@(h:t) -- t)
+    Popvins: Vector, [InsVec] -> [InsVec],
+    /// int vec: (_@(h:t) -- t)
+    Popvi64: Vector, [IntVec] -> [IntVec],
+    /// float vec: (_@(h:t) -- t)
+    Popvf64: Vector, [FloatVec] -> [FloatVec],
+    /// int: (i -- )
+    /// ins vec: (v -- v)
+    /// ins: ( -- v[i])
+    Readvins: Vector, [Int] -> [Ins],
+    /// int: (i -- v[i])
+    /// int vec: (v -- v)
+    Readvi64: Vector, [Int] -> [Int],
+    /// int: (i -- )
+    /// float vec: (v -- v)
+    /// float: ( -- v[i])
+    Readvf64: Vector, [Int] -> [Float],
+    /// int: (i -- )
+    /// ins: (e -- )
+    /// ins vec: (v -- v)
+    /// v[i] = e
+    Writevins: Vector, [Int, Ins, InsVec] -> [InsVec],
+    /// int: (e i -- )
+    /// int vec: (v -- v)
+    /// v[i] = e
+    Writevi64: Vector, [Int, Int, IntVec] -> [IntVec],
+    /// int: (i -- )
+    /// float: (e -- )
+    /// float vec: (v -- v)
+    /// v[i] = e
+    Writevf64: Vector, [Int, Float, FloatVec] -> [FloatVec],
+
+    // Auxiliary operations
+    /// int: ( -- 0)
+    Zeroi64: Auxiliary, [] -> [Int],
+
+    // Instruction construction
+    /// int: (n -- )
+    /// ins: ( -- PlainOp)
+    CreatePlain: Construction, [Int] -> [Ins],
+    /// ins vec: (b -- )
+    /// ins: ( -- BasicBlock(b))
+    CreateBasicBlock: Construction, [InsVec] -> [Ins],
+    /// ins vec: (b -- )
+    /// ins: ( -- Loop(b))
+    CreateLoop: Construction, [InsVec] -> [Ins],
+    /// ins vec: (t f -- )
+    /// ins: ( -- If(f, t))
+    CreateIf: Construction, [InsVec, InsVec] -> [Ins],
+    /// int: (n -- )
+    /// ins: ( -- Pushi64(n))
+    CreatePushi64: Construction, [Int] -> [Ins],
+    /// float: (n -- )
+    /// ins: ( -- Pushf64(n))
+    CreatePushf64: Construction, [Float] -> [Ins],
+    /// bool: (b -- )
+    /// ins: ( -- Pushb(b))
+    CreatePushb: Construction, [Bool] -> [Ins],
+    /// int vec: (v -- )
+    /// ins: ( -- Pushi64v(v))
+    CreatePushi64v: Construction, [IntVec] -> [Ins],
+    /// float vec: (v -- )
+    /// ins: ( -- Pushf64v(v))
+    CreatePushf64v: Construction, [FloatVec] -> [Ins],
+
+    // Execution control
+    /// exe: (e -- )
+    Return: Control, [Exe] -> [],
+    /// exe: (e -- )
+    /// ins: ( -- e)
+    Yield: Control, [Exe] -> [Ins],
+    /// ins: (e -- )
+    /// exe: ( -- e)
+    Call: Control, [Ins] -> [Exe],
+    /// Does nothing
+    Nop: Control, [] -> [],
+
+    // External communication
+    /// ins: (i -- )
+    Provide: Communication, [Ins] -> [],
+
+    // Input registers
+    /// int: (i -- inputs.ints[i])
+    Ini64: Input, [Int] -> [Int],
+    /// int: (i -- )
+    /// float: ( -- inputs.floats[i])
+    Inf64: Input, [Int] -> [Float],
+    /// int: (i -- )
+    /// bool: ( -- inputs.bools[i])
+    Inb: Input, [Int] -> [Bool],
+    /// int: (i -- )
+    /// int vec: ( -- inputs.int_vecs[i])
+    Ini64v: Input, [Int] -> [IntVec],
+    /// int: (i -- )
+    /// float vec: ( -- inputs.float_vecs[i])
+    Inf64v: Input, [Int] -> [FloatVec],
+
+    // String operations
+    /// string: (a b -- (a + b))
+    Concats: String, [String, String] -> [String],
+    /// string: (s -- )
+    /// int: ( -- number of chars in s)
+    Lens: String, [String] -> [Int],
+    /// string: (s -- chars start..end of s)
+    /// int: (start end -- )
+    Substrs: String, [Int, Int, String] -> [String],
+    /// string: (s p -- )
+    /// int: ( -- char index of first p in s or -1)
+    Indexofs: String, [String, String] -> [Int],
+    /// string: (s p -- parts of s between each p, first on top)
+    ///
+    /// If p is empty, s is split on whitespace instead.
+    Splits: String, [String, String] -> [String],
+    /// string: (s -- s)
+    /// int: (i -- )
+    /// char: ( -- s[i])
+    Charats: String, [Int] -> [Char],
+    /// string: (a b -- )
+    /// bool: ( -- (a < b))
+    Less: String, [String, String] -> [Bool],
+    /// string: (a b -- )
+    /// bool: ( -- (a > b))
+    Grts: String, [String, String] -> [Bool],
+    /// string: (a b -- )
+    /// bool: ( -- (a == b))
+    Eqs: String, [String, String] -> [Bool],
+    /// string: (a b -- )
+    /// bool: ( -- (a != b))
+    Neqs: String, [String, String] -> [Bool],
+
+    // Char operations
+    /// char: (c -- )
+    /// string: ( -- c)
+    Ctos: Char, [Char] -> [String],
+    /// int: (n -- )
+    /// char: ( -- char with code point n)
+    Itoc: Char, [Int] -> [Char],
+    /// char: (c -- )
+    /// int: ( -- code point of c)
+    Ctoi: Char, [Char] -> [Int],
+    /// char: (a b -- )
+    /// bool: ( -- (a < b))
+    Lesc: Char, [Char, Char] -> [Bool],
+    /// char: (a b -- )
+    /// bool: ( -- (a > b))
+    Grtc: Char, [Char, Char] -> [Bool],
+    /// char: (a b -- )
+    /// bool: ( -- (a == b))
+    Eqc: Char, [Char, Char] -> [Bool],
+    /// char: (a b -- )
+    /// bool: ( -- (a != b))
+    Neqc: Char, [Char, Char] -> [Bool],
+
+    // Text stack manipulation
+    /// int: (b -- )
+    /// string: (a b.. -- b.. a)
+    Rots: Stack, [Int, String] -> [String],
+    /// int: (b -- )
+    /// char: (a b.. -- b.. a)
+    Rotc: Stack, [Int, Char] -> [Char],
+    /// int: (b -- )
+    /// string: (a b.. -- a b.. a)
+    Copys: Stack, [Int, String] -> [String, String],
+    /// int: (b -- )
+    /// char: (a b.. -- a b.. a)
+    Copyc: Stack, [Int, Char] -> [Char, Char],
+    /// string: (s -- )
+    Pops: Stack, [String] -> [],
+    /// char: (c -- )
+    Popc: Stack, [Char] -> [],
+    /// string: (s -- )
+    /// ins: ( -- Pushs(s))
+    CreatePushs: Construction, [String] -> [Ins],
+    /// char: (c -- )
+    /// ins: ( -- Pushc(c))
+    CreatePushc: Construction, [Char] -> [Ins],
+
+    // Bool vector operations
+    /// int: (b -- )
+    /// bool vec: (a b.. -- b.. a)
+    Rotbv: Stack, [Int, BoolVec] -> [BoolVec],
+    /// int: (b -- )
+    /// bool vec: (a b.. -- a b.. a)
+    Copybv: Stack, [Int, BoolVec] -> [BoolVec, BoolVec],
+    /// bool vec: (a -- )
+    Popbv: Stack, [BoolVec] -> [],
+    /// bool: (a -- )
+    /// bool vec: (v -- a:v)
+    Pushvb: Vector, [Bool, BoolVec] -> [BoolVec],
+    /// bool vec: (_@(h:t) -- t)
+    Popvb: Vector, [BoolVec] -> [BoolVec],
+    /// int: (i -- )
+    /// bool vec: (v -- v)
+    /// bool: ( -- v[i])
+    Readvb: Vector, [Int] -> [Bool],
+    /// int: (i -- )
+    /// bool: (e -- )
+    /// bool vec: (v -- v)
+    /// v[i] = e
+    Writevb: Vector, [Int, Bool, BoolVec] -> [BoolVec],
+    /// bool vec: (v -- )
+    /// ins: ( -- Pushbv(v))
+    CreatePushbv: Construction, [BoolVec] -> [Ins],
+    /// int: (i -- )
+    /// bool vec: ( -- inputs.bool_vecs[i])
+    Inbv: Input, [Int] -> [BoolVec],
+
+    // Higher-order vector operations
+    /// ins: (f -- )
+    /// int vec: (v -- )
+    /// exe: ( -- map block)
+    ///
+    /// The block pushes an empty int vec, then for each element of v pushes it to the int stack, runs f and appends
+    /// the int f leaves to the new vec.
+    Mapvi64: HigherOrder, [Ins, IntVec] -> [Exe],
+    /// ins: (f -- )
+    /// int vec: (v -- )
+    /// exe: ( -- filter block)
+    ///
+    /// The block pushes an empty int vec, then for each element of v pushes it to the int stack, runs f and appends
+    /// the element to the new vec if f leaves true on the bool stack.
+    Filtervi64: HigherOrder, [Ins, IntVec] -> [Exe],
+    /// ins: (f -- )
+    /// int vec: (v -- )
+    /// exe: ( -- fold block)
+    ///
+    /// The block pushes each element of v to the int stack and runs f, so f folds it into the accumulator below it.
+    Foldvi64: HigherOrder, [Ins, IntVec] -> [Exe],
+    /// ins: (f -- )
+    /// int vec: (a b -- )
+    /// exe: ( -- zip block)
+    ///
+    /// The block pushes an empty int vec, then for each pair of elements of a and b pushes both to the int stack,
+    /// runs f and appends the int f leaves to the new vec. It stops at the end of the shorter vec.
+    Zipvi64: HigherOrder, [Ins, IntVec, IntVec] -> [Exe],
+    /// ins: (f -- )
+    /// float vec: (v -- )
+    /// exe: ( -- map block)
+    ///
+    /// The block pushes an empty float vec, then for each element of v pushes it to the float stack, runs f and appends
+    /// the float f leaves to the new vec.
+    Mapvf64: HigherOrder, [Ins, FloatVec] -> [Exe],
+    /// ins: (f -- )
+    /// float vec: (v -- )
+    /// exe: ( -- filter block)
+    ///
+    /// The block pushes an empty float vec, then for each element of v pushes it to the float stack, runs f and appends
+    /// the element to the new vec if f leaves true on the bool stack.
+    Filtervf64: HigherOrder, [Ins, FloatVec] -> [Exe],
+    /// ins: (f -- )
+    /// float vec: (v -- )
+    /// exe: ( -- fold block)
+    ///
+    /// The block pushes each element of v to the float stack and runs f, so f folds it into the accumulator below it.
+    Foldvf64: HigherOrder, [Ins, FloatVec] -> [Exe],
+    /// ins: (f -- )
+    /// float vec: (a b -- )
+    /// exe: ( -- zip block)
+    ///
+    /// The block pushes an empty float vec, then for each pair of elements of a and b pushes both to the float stack,
+    /// runs f and appends the float f leaves to the new vec. It stops at the end of the shorter vec.
+    Zipvf64: HigherOrder, [Ins, FloatVec, FloatVec] -> [Exe],
+
+    // Bulk vector operations
+    /// int vec: (v -- )
+    /// int: ( -- length of v)
+    Lenvi64: Vector, [IntVec] -> [Int],
+    /// int vec: (v -- v reversed)
+    Revvi64: Vector, [IntVec] -> [IntVec],
+    /// int vec: (a b -- a ++ b)
+    Catvi64: Vector, [IntVec, IntVec] -> [IntVec],
+    /// int: (start end -- )
+    /// int vec: (v -- v[start..end])
+    Slicevi64: Vector, [Int, Int, IntVec] -> [IntVec],
+    /// int: (n -- )
+    /// int vec: (v -- first n elements of v)
+    Takevi64: Vector, [Int, IntVec] -> [IntVec],
+    /// int: (n -- )
+    /// int vec: (v -- v without its first n elements)
+    Dropvi64: Vector, [Int, IntVec] -> [IntVec],
+    /// int vec: (v -- v sorted in ascending order)
+    Sortvi64: Vector, [IntVec] -> [IntVec],
+    /// int vec: (v -- )
+    /// int: ( -- sum of v)
+    Sumvi64: Vector, [IntVec] -> [Int],
+    /// int vec: (v -- )
+    /// int: ( -- product of v)
+    Prodvi64: Vector, [IntVec] -> [Int],
+    /// int vec: (v -- )
+    /// int: ( -- smallest element of v)
+    Minvi64: Vector, [IntVec] -> [Int],
+    /// int vec: (v -- )
+    /// int: ( -- largest element of v)
+    Maxvi64: Vector, [IntVec] -> [Int],
+    /// int vec: (v -- )
+    /// float: ( -- mean of v)
+    Meanvi64: Vector, [IntVec] -> [Float],
+    /// int vec: (a b -- )
+    /// int: ( -- dot product of a and b, ignoring the excess of the longer vec)
+    Dotvi64: Vector, [IntVec, IntVec] -> [Int],
+    /// float vec: (v -- )
+    /// int: ( -- length of v)
+    Lenvf64: Vector, [FloatVec] -> [Int],
+    /// float vec: (v -- v reversed)
+    Revvf64: Vector, [FloatVec] -> [FloatVec],
+    /// float vec: (a b -- a ++ b)
+    Catvf64: Vector, [FloatVec, FloatVec] -> [FloatVec],
+    /// int: (start end -- )
+    /// float vec: (v -- v[start..end])
+    Slicevf64: Vector, [Int, Int, FloatVec] -> [FloatVec],
+    /// int: (n -- )
+    /// float vec: (v -- first n elements of v)
+    Takevf64: Vector, [Int, FloatVec] -> [FloatVec],
+    /// int: (n -- )
+    /// float vec: (v -- v without its first n elements)
+    Dropvf64: Vector, [Int, FloatVec] -> [FloatVec],
+    /// float vec: (v -- v sorted in ascending order)
+    Sortvf64: Vector, [FloatVec] -> [FloatVec],
+    /// float vec: (v -- )
+    /// float: ( -- sum of v)
+    Sumvf64: Vector, [FloatVec] -> [Float],
+    /// float vec: (v -- )
+    /// float: ( -- product of v)
+    Prodvf64: Vector, [FloatVec] -> [Float],
+    /// float vec: (v -- )
+    /// float: ( -- smallest element of v)
+    Minvf64: Vector, [FloatVec] -> [Float],
+    /// float vec: (v -- )
+    /// float: ( -- largest element of v)
+    Maxvf64: Vector, [FloatVec] -> [Float],
+    /// float vec: (v -- )
+    /// float: ( -- mean of v)
+    Meanvf64: Vector, [FloatVec] -> [Float],
+    /// float vec: (a b -- )
+    /// float: ( -- dot product of a and b, ignoring the excess of the longer vec)
+    Dotvf64: Vector, [FloatVec, FloatVec] -> [Float],
+
+    // Counted loops
+    /// ins vec: (b -- )
+    /// ins: ( -- DoTimes(b))
+    CreateDoTimes: Construction, [InsVec] -> [Ins],
+    /// ins vec: (b -- )
+    /// ins: ( -- DoRange(b))
+    CreateDoRange: Construction, [InsVec] -> [Ins],
+    /// ins vec: (b -- )
+    /// ins: ( -- While(b))
+    CreateWhile: Construction, [InsVec] -> [Ins],
+
+    // Exe stack combinators
+    /// exe: (b a -- a)
+    Kexe: Control, [Exe, Exe] -> [Exe],
+    /// exe: (c b a -- (b c) c a)
+    Sexe: Control, [Exe, Exe, Exe] -> [Exe, Exe, Exe],
+    /// exe: (a -- (Yexe a) a)
+    Yexe: Control, [Exe] -> [Exe, Exe],
+    /// exe: (a -- a a)
+    Dupexe: Control, [Exe] -> [Exe, Exe],
+    /// exe: (a b -- b a)
+    Swapexe: Control, [Exe, Exe] -> [Exe, Exe],
+    /// int: (b -- )
+    /// exe: (a b.. -- b.. a)
+    Rotexe: Control, [Int, Exe] -> [Exe],
+    /// exe: (.. -- )
+    Flushexe: Control, [] -> [],
+    /// int: (start end -- )
+    /// exe: (a -- DoRange((a)))
+    DoRangeexe: Control, [Int, Int, Exe] -> [Exe],
+    /// int: (n -- )
+    /// exe: (a -- DoTimes((a)))
+    DoTimesexe: Control, [Int, Exe] -> [Exe],
+
+    // Generic stack manipulation
+    /// ins: (.. -- ..)
+    /// int: ( -- depth)
+    Depthins: Stack, [] -> [Int],
+    /// ins: (a -- a a)
+    Dupins: Stack, [Ins] -> [Ins, Ins],
+    /// ins: (a b -- b a)
+    Swapins: Stack, [Ins, Ins] -> [Ins, Ins],
+    /// ins: (.. -- )
+    Flushins: Stack, [] -> [],
+    /// int: (b -- )
+    /// ins: (b.. a -- a b..)
+    Shoveins: Stack, [Int, Ins] -> [Ins],
+    /// int: (.. -- .. depth)
+    Depthi64: Stack, [] -> [Int],
+    /// int: (a -- a a)
+    Dupi64: Stack, [Int] -> [Int, Int],
+    /// int: (a b -- b a)
+    Swapi64: Stack, [Int, Int] -> [Int, Int],
+    /// int: (.. -- )
+    Flushi64: Stack, [] -> [],
+    /// int: (b -- )
+    /// int: (b.. a -- a b..)
+    Shovei64: Stack, [Int, Int] -> [Int],
+    /// float: (.. -- ..)
+    /// int: ( -- depth)
+    Depthf64: Stack, [] -> [Int],
+    /// float: (a -- a a)
+    Dupf64: Stack, [Float] -> [Float, Float],
+    /// float: (a b -- b a)
+    Swapf64: Stack, [Float, Float] -> [Float, Float],
+    /// float: (.. -- )
+    Flushf64: Stack, [] -> [],
+    /// int: (b -- )
+    /// float: (b.. a -- a b..)
+    Shovef64: Stack, [Int, Float] -> [Float],
+    /// bool: (.. -- ..)
+    /// int: ( -- depth)
+    Depthb: Stack, [] -> [Int],
+    /// bool: (a -- a a)
+    Dupb: Stack, [Bool] -> [Bool, Bool],
+    /// bool: (a b -- b a)
+    Swapb: Stack, [Bool, Bool] -> [Bool, Bool],
+    /// bool: (.. -- )
+    Flushb: Stack, [] -> [],
+    /// int: (b -- )
+    /// bool: (b.. a -- a b..)
+    Shoveb: Stack, [Int, Bool] -> [Bool],
+    /// string: (.. -- ..)
+    /// int: ( -- depth)
+    Depths: Stack, [] -> [Int],
+    /// string: (a -- a a)
+    Dups: Stack, [String] -> [String, String],
+    /// string: (a b -- b a)
+    Swaps: Stack, [String, String] -> [String, String],
+    /// string: (.. -- )
+    Flushs: Stack, [] -> [],
+    /// int: (b -- )
+    /// string: (b.. a -- a b..)
+    Shoves: Stack, [Int, String] -> [String],
+    /// char: (.. -- ..)
+    /// int: ( -- depth)
+    Depthc: Stack, [] -> [Int],
+    /// char: (a -- a a)
+    Dupc: Stack, [Char] -> [Char, Char],
+    /// char: (a b -- b a)
+    Swapc: Stack, [Char, Char] -> [Char, Char],
+    /// char: (.. -- )
+    Flushc: Stack, [] -> [],
+    /// int: (b -- )
+    /// char: (b.. a -- a b..)
+    Shovec: Stack, [Int, Char] -> [Char],
+    /// ins vec: (.. -- ..)
+    /// int: ( -- depth)
+    Depthinsv: Stack, [] -> [Int],
+    /// ins vec: (a -- a a)
+    Dupinsv: Stack, [InsVec] -> [InsVec, InsVec],
+    /// ins vec: (a b -- b a)
+    Swapinsv: Stack, [InsVec, InsVec] -> [InsVec, InsVec],
+    /// ins vec: (.. -- )
+    Flushinsv: Stack, [] -> [],
+    /// int: (b -- )
+    /// ins vec: (b.. a -- a b..)
+    Shoveinsv: Stack, [Int, InsVec] -> [InsVec],
+    /// int vec: (.. -- ..)
+    /// int: ( -- depth)
+    Depthi64v: Stack, [] -> [Int],
+    /// int vec: (a -- a a)
+    Dupi64v: Stack, [IntVec] -> [IntVec, IntVec],
+    /// int vec: (a b -- b a)
+    Swapi64v: Stack, [IntVec, IntVec] -> [IntVec, IntVec],
+    /// int vec: (.. -- )
+    Flushi64v: Stack, [] -> [],
+    /// int: (b -- )
+    /// int vec: (b.. a -- a b..)
+    Shovei64v: Stack, [Int, IntVec] -> [IntVec],
+    /// float vec: (.. -- ..)
+    /// int: ( -- depth)
+    Depthf64v: Stack, [] -> [Int],
+    /// float vec: (a -- a a)
+    Dupf64v: Stack, [FloatVec] -> [FloatVec, FloatVec],
+    /// float vec: (a b -- b a)
+    Swapf64v: Stack, [FloatVec, FloatVec] -> [FloatVec, FloatVec],
+    /// float vec: (.. -- )
+    Flushf64v: Stack, [] -> [],
+    /// int: (b -- )
+    /// float vec: (b.. a -- a b..)
+    Shovef64v: Stack, [Int, FloatVec] -> [FloatVec],
+    /// bool vec: (.. -- ..)
+    /// int: ( -- depth)
+    Depthbv: Stack, [] -> [Int],
+    /// bool vec: (a -- a a)
+    Dupbv: Stack, [BoolVec] -> [BoolVec, BoolVec],
+    /// bool vec: (a b -- b a)
+    Swapbv: Stack, [BoolVec, BoolVec] -> [BoolVec, BoolVec],
+    /// bool vec: (.. -- )
+    Flushbv: Stack, [] -> [],
+    /// int: (b -- )
+    /// bool vec: (b.. a -- a b..)
+    Shovebv: Stack, [Int, BoolVec] -> [BoolVec],
+    /// exe: (.. -- ..)
+    /// int: ( -- depth)
+    Depthexe: Control, [] -> [Int],
+    /// int: (b -- )
+    /// exe: (b.. a -- a b..)
+    Shoveexe: Control, [Int, Exe] -> [Exe],
+
+    // Names and bindings
+    /// exe: (Name(n) -- )
+    /// name: ( -- n)
+    ///
+    /// Quotes the next name so it is pushed to the name stack even if it is bound.
+    Quotename: Name, [Exe] -> [Name],
+    /// name: (n -- )
+    /// ins: ( -- Name(n))
+    CreateName: Construction, [Name] -> [Ins],
+    /// int: (a -- )
+    /// name: ( -- a)
+    Iton: Name, [Int] -> [Name],
+    /// name: (n -- )
+    /// ins: (i -- )
+    /// Binds n to i.
+    Defineins: Name, [Name, Ins] -> [],
+    /// name: (n -- )
+    /// int: (a -- )
+    /// Binds n to Pushi64(a).
+    Definei64: Name, [Name, Int] -> [],
+    /// name: (n -- )
+    /// float: (a -- )
+    /// Binds n to Pushf64(a).
+    Definef64: Name, [Name, Float] -> [],
+    /// name: (n -- )
+    /// bool: (a -- )
+    /// Binds n to Pushb(a).
+    Defineb: Name, [Name, Bool] -> [],
+    /// name: (n -- )
+    /// string: (a -- )
+    /// Binds n to Pushs(a).
+    Defines: Name, [Name, String] -> [],
+    /// name: (n -- )
+    /// char: (a -- )
+    /// Binds n to Pushc(a).
+    Definec: Name, [Name, Char] -> [],
+    /// int: (b -- )
+    /// name: (a b.. -- b.. a)
+    Rotn: Name, [Int, Name] -> [Name],
+    /// int: (b -- )
+    /// name: (a b.. -- a b.. a)
+    Copyn: Name, [Int, Name] -> [Name, Name],
+    /// name: (a -- )
+    Popn: Name, [Name] -> [],
+    /// name: (.. -- ..)
+    /// int: ( -- depth)
+    Depthn: Name, [] -> [Int],
+    /// name: (a -- a a)
+    Dupn: Name, [Name] -> [Name, Name],
+    /// name: (a b -- b a)
+    Swapn: Name, [Name, Name] -> [Name, Name],
+    /// name: (.. -- )
+    Flushn: Name, [] -> [],
+    /// int: (b -- )
+    /// name: (b.. a -- a b..)
+    Shoven: Name, [Int, Name] -> [Name],
+
+    // Fixed input registers
+    /// int: ( -- inputs.ints[0])
+    In0i64: Input, [] -> [Int],
+    /// int: ( -- inputs.ints[1])
+    In1i64: Input, [] -> [Int],
+    /// int: ( -- inputs.ints[2])
+    In2i64: Input, [] -> [Int],
+    /// int: ( -- inputs.ints[3])
+    In3i64: Input, [] -> [Int],
+    /// float: ( -- inputs.floats[0])
+    In0f64: Input, [] -> [Float],
+    /// float: ( -- inputs.floats[1])
+    In1f64: Input, [] -> [Float],
+    /// float: ( -- inputs.floats[2])
+    In2f64: Input, [] -> [Float],
+    /// float: ( -- inputs.floats[3])
+    In3f64: Input, [] -> [Float],
+    /// bool: ( -- inputs.bools[0])
+    In0b: Input, [] -> [Bool],
+    /// bool: ( -- inputs.bools[1])
+    In1b: Input, [] -> [Bool],
+    /// bool: ( -- inputs.bools[2])
+    In2b: Input, [] -> [Bool],
+    /// bool: ( -- inputs.bools[3])
+    In3b: Input, [] -> [Bool],
+    /// int vec: ( -- inputs.int_vecs[0])
+    In0i64v: Input, [] -> [IntVec],
+    /// int vec: ( -- inputs.int_vecs[1])
+    In1i64v: Input, [] -> [IntVec],
+    /// int vec: ( -- inputs.int_vecs[2])
+    In2i64v: Input, [] -> [IntVec],
+    /// int vec: ( -- inputs.int_vecs[3])
+    In3i64v: Input, [] -> [IntVec],
+    /// float vec: ( -- inputs.float_vecs[0])
+    In0f64v: Input, [] -> [FloatVec],
+    /// float vec: ( -- inputs.float_vecs[1])
+    In1f64v: Input, [] -> [FloatVec],
+    /// float vec: ( -- inputs.float_vecs[2])
+    In2f64v: Input, [] -> [FloatVec],
+    /// float vec: ( -- inputs.float_vecs[3])
+    In3f64v: Input, [] -> [FloatVec],
+    /// bool vec: ( -- inputs.bool_vecs[0])
+    In0bv: Input, [] -> [BoolVec],
+    /// bool vec: ( -- inputs.bool_vecs[1])
+    In1bv: Input, [] -> [BoolVec],
+    /// bool vec: ( -- inputs.bool_vecs[2])
+    In2bv: Input, [] -> [BoolVec],
+    /// bool vec: ( -- inputs.bool_vecs[3])
+    In3bv: Input, [] -> [BoolVec],
+
+    // Indexed counted loops
+    /// ins vec: (b -- )
+    /// ins: ( -- DoTimes(b) which pushes its index)
+    CreateDoTimesIndexed: Construction, [InsVec] -> [Ins],
+
+    // Yanking, which clamps the position to the bottom of the stack instead of failing like Rot* and Copy*
+    /// int: (b -- )
+    /// exe: (a b.. -- b.. a)
+    Yankexe: Control, [Int, Exe] -> [Exe],
+    /// int: (b -- )
+    /// exe: (a b.. -- a b.. a)
+    YankDupexe: Control, [Int, Exe] -> [Exe, Exe],
+    /// int: (b -- )
+    /// ins: (a b.. -- b.. a)
+    Yankins: Stack, [Int, Ins] -> [Ins],
+    /// int: (b -- )
+    /// ins: (a b.. -- a b.. a)
+    YankDupins: Stack, [Int, Ins] -> [Ins, Ins],
+    /// int: (b -- )
+    /// int: (a b.. -- b.. a)
+    Yanki64: Stack, [Int, Int] -> [Int],
+    /// int: (b -- )
+    /// int: (a b.. -- a b.. a)
+    YankDupi64: Stack, [Int, Int] -> [Int, Int],
+    /// int: (b -- )
+    /// float: (a b.. -- b.. a)
+    Yankf64: Stack, [Int, Float] -> [Float],
+    /// int: (b -- )
+    /// float: (a b.. -- a b.. a)
+    YankDupf64: Stack, [Int, Float] -> [Float, Float],
+    /// int: (b -- )
+    /// bool: (a b.. -- b.. a)
+    Yankb: Stack, [Int, Bool] -> [Bool],
+    /// int: (b -- )
+    /// bool: (a b.. -- a b.. a)
+    YankDupb: Stack, [Int, Bool] -> [Bool, Bool],
+    /// int: (b -- )
+    /// string: (a b.. -- b.. a)
+    Yanks: Stack, [Int, String] -> [String],
+    /// int: (b -- )
+    /// string: (a b.. -- a b.. a)
+    YankDups: Stack, [Int, String] -> [String, String],
+    /// int: (b -- )
+    /// char: (a b.. -- b.. a)
+    Yankc: Stack, [Int, Char] -> [Char],
+    /// int: (b -- )
+    /// char: (a b.. -- a b.. a)
+    YankDupc: Stack, [Int, Char] -> [Char, Char],
+    /// int: (b -- )
+    /// ins vec: (a b.. -- b.. a)
+    Yankinsv: Stack, [Int, InsVec] -> [InsVec],
+    /// int: (b -- )
+    /// ins vec: (a b.. -- a b.. a)
+    YankDupinsv: Stack, [Int, InsVec] -> [InsVec, InsVec],
+    /// int: (b -- )
+    /// int vec: (a b.. -- b.. a)
+    Yanki64v: Stack, [Int, IntVec] -> [IntVec],
+    /// int: (b -- )
+    /// int vec: (a b.. -- a b.. a)
+    YankDupi64v: Stack, [Int, IntVec] -> [IntVec, IntVec],
+    /// int: (b -- )
+    /// float vec: (a b.. -- b.. a)
+    Yankf64v: Stack, [Int, FloatVec] -> [FloatVec],
+    /// int: (b -- )
+    /// float vec: (a b.. -- a b.. a)
+    YankDupf64v: Stack, [Int, FloatVec] -> [FloatVec, FloatVec],
+    /// int: (b -- )
+    /// bool vec: (a b.. -- b.. a)
+    Yankbv: Stack, [Int, BoolVec] -> [BoolVec],
+    /// int: (b -- )
+    /// bool vec: (a b.. -- a b.. a)
+    YankDupbv: Stack, [Int, BoolVec] -> [BoolVec, BoolVec],
+    /// int: (b -- )
+    /// name: (a b.. -- b.. a)
+    Yankn: Name, [Int, Name] -> [Name],
+    /// int: (b -- )
+    /// name: (a b.. -- a b.. a)
+    YankDupn: Name, [Int, Name] -> [Name, Name],
+
+    // Flushing bindings
+    /// Unbinds every name.
+    Flushbindings: Name, [] -> [],
+
+    // Tags
+    /// int: (n -- )
+    /// ins: ( -- Tag(n))
+    CreateTag: Construction, [Int] -> [Ins],
+    /// int: (n -- )
+    /// ins: ( -- TagRef(n))
+    CreateTagRef: Construction, [Int] -> [Ins],
+    /// Removes every tagged instruction.
+    Flushtags: Control, [] -> [],
+}
+
+impl PlainOp {
+    /// The stable code of the operation, which `CreatePlain` takes to construct it.
+    #[inline]
+    pub fn to_code(self) -> u16 {
+        self as u16
+    }
+
+    /// The operation with the code `code`, if there is one.
+    #[inline]
+    pub fn from_code(code: u16) -> Option<PlainOp> {
+        PlainOp::ALL.get(code as usize).cloned()
+    }
+}
+
+impl rand::Rand for PlainOp {
+    fn rand<R: rand::Rng>(rng: &mut R) -> Self {
+        PlainOp::ALL[rng.gen_range(0, PlainOp::ALL.len())]
+    }
+}
+
+impl HeapSizeOf for PlainOp {
+    fn heap_size_of_children(&self) -> usize {
+        0
+    }
+}
@@ -0,0 +1,113 @@
+use super::{BlockBuilder, Gene, InstructionSet, SimpleInstruction};
+
+use rand::Rng;
+
+/// A gene of a `Plush` genome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlushGene {
+    pub gene: Gene,
+    /// The number of block bodies ended after the gene.
+    pub close: u32,
+    /// Silent genes are skipped entirely when translating, along with their closes.
+    pub silent: bool,
+}
+
+impl PlushGene {
+    pub fn new(gene: Gene) -> PlushGene {
+        PlushGene {
+            gene,
+            close: 0,
+            silent: false,
+        }
+    }
+}
+
+/// A linear genome where block structure is carried by the close count of each gene rather than by separate genes,
+/// so mutating a single gene can open, extend or end a block.
+///
+/// `Open` genes start a block, and each close ends a body of the innermost open block, as done by `BlockBuilder`.
+/// `Close` genes still end a body on their own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Plush {
+    pub genes: Vec<PlushGene>,
+}
+
+impl Plush {
+    pub fn new(genes: Vec<PlushGene>) -> Plush {
+        Plush { genes }
+    }
+
+    /// Create a genome of `len` genes drawn from `instructions`.
+    ///
+    /// After each gene another close is added with a chance of `close_rate`, so the close counts are geometrically
+    /// distributed, up to `MAX_CLOSE`. Each gene is silent with a chance of `silent_rate`.
+    pub fn new_rand<R: Rng>(rng: &mut R,
+                            instructions: &InstructionSet,
+                            len: usize,
+                            close_rate: f64,
+                            silent_rate: f64)
+                            -> Plush {
+        Plush {
+            genes: (0..len)
+                .map(|_| {
+                    PlushGene {
+                        gene: instructions.gen_gene(rng),
+                        close: rand_close(close_rate, rng),
+                        silent: rng.gen::<f64>() < silent_rate,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Change each gene with a chance of `rate` by replacing it, perturbing its constant, redrawing its close count
+    /// or toggling whether it is silent.
+    pub fn mutate<R: Rng>(&mut self, rate: f64, close_rate: f64, instructions: &InstructionSet, rng: &mut R) {
+        for gene in &mut self.genes {
+            if rng.gen::<f64>() >= rate {
+                continue;
+            }
+            match rng.gen_range(0, 4usize) {
+                0 => gene.gene = instructions.gen_gene(rng),
+                1 => instructions.perturb(&mut gene.gene, rng),
+                2 => gene.close = rand_close(close_rate, rng),
+                _ => gene.silent = !gene.silent,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.genes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+}
+
+/// The most closes drawn for a gene, so a `close_rate` of 1 or more still ends.
+pub const MAX_CLOSE: u32 = 32;
+
+fn rand_close<R: Rng>(close_rate: f64, rng: &mut R) -> u32 {
+    let mut close = 0;
+    while close < MAX_CLOSE && rng.gen::<f64>() < close_rate {
+        close += 1;
+    }
+    close
+}
+
+/// Translates the genome into a `BasicBlock` with the blocks its genes open nested inside of it.
+impl From<&Plush> for SimpleInstruction {
+    fn from(plush: &Plush) -> SimpleInstruction {
+        let mut builder = BlockBuilder::new();
+        for gene in plush.genes.iter().filter(|g| !g.silent) {
+            builder.add(&gene.gene);
+            for _ in 0..gene.close {
+                if !builder.close() {
+                    break;
+                }
+            }
+        }
+        builder.finish()
+    }
+}
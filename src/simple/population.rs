@@ -0,0 +1,228 @@
+use Machine;
+use super::{Chromosome, Crossover, InstructionSet, SimpleInstruction, Selection, rank};
+
+use std::f64;
+use rand::Rng;
+use rand::distributions::Exp;
+
+/// Parameters controlling how a `Population` produces each new generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationConfig {
+    /// The number of best individuals copied unchanged into the next generation.
+    pub elitism: usize,
+    /// The chance that a child is produced by crossing two parents rather than by cloning one.
+    pub crossover_rate: f64,
+    /// The chance that a child is mutated after it is produced.
+    pub mutation_rate: f64,
+    /// The maximum number of mutations applied to a mutated child.
+    pub max_mutations: usize,
+    /// The mean distance in genes between two mutations of the same child.
+    pub mutation_spacing: f64,
+    /// The instructions random and mutated genes are drawn from.
+    pub instructions: InstructionSet,
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        PopulationConfig {
+            elitism: 1,
+            crossover_rate: 0.5,
+            mutation_rate: 0.5,
+            max_mutations: 4,
+            mutation_spacing: 8.0,
+            instructions: InstructionSet::default(),
+        }
+    }
+}
+
+/// A `Chromosome` along with the fitness it scored, if it has been evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Individual {
+    pub chromosome: Chromosome,
+    /// Higher is better. This is `None` until the individual is evaluated.
+    pub fitness: Option<f64>,
+    /// The fitness scored on each test case, if the individual was evaluated with `Population::evaluate_cases`.
+    pub cases: Vec<f64>,
+}
+
+impl Individual {
+    pub fn new(chromosome: Chromosome) -> Individual {
+        Individual {
+            chromosome,
+            fitness: None,
+            cases: Vec::new(),
+        }
+    }
+
+    /// The fitness used for comparisons, where unevaluated or NaN fitness is worse than anything else.
+    pub fn score(&self) -> f64 {
+        match self.fitness {
+            Some(f) if !f.is_nan() => f,
+            _ => f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// A set of `Chromosome`s which is evaluated and bred one generation at a time.
+///
+/// All randomness is drawn from the provided `rng`, so seeding it (e.g. with `rand::XorShiftRng::from_seed`) makes
+/// a run reproducible as long as the fitness function is deterministic.
+#[derive(Debug, Clone)]
+pub struct Population<R> {
+    pub individuals: Vec<Individual>,
+    pub config: PopulationConfig,
+    /// The number of generations which have been produced so far.
+    pub generation: usize,
+    rng: R,
+}
+
+impl<R> Population<R>
+    where R: Rng
+{
+    pub fn new(rng: R, chromosomes: Vec<Chromosome>, config: PopulationConfig) -> Self {
+        Population {
+            individuals: chromosomes.into_iter().map(Individual::new).collect(),
+            config,
+            generation: 0,
+            rng,
+        }
+    }
+
+    /// Create a population of `size` random chromosomes, each with `len` genes drawn from `config.instructions` and up
+    /// to `crossovers` crossover points.
+    pub fn new_rand(mut rng: R,
+                    size: usize,
+                    len: usize,
+                    crossovers: usize,
+                    config: PopulationConfig)
+                    -> Self {
+        let chromosomes = (0..size)
+            .map(|_| Chromosome::new_rand(&mut rng, &config.instructions, len, crossovers))
+            .collect();
+        Population::new(rng, chromosomes, config)
+    }
+
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    /// Evaluate every individual by loading it into a fresh `Machine` from `new_machine` and scoring it with
+    /// `fitness`, which is responsible for running the machine. An individual too large to fit in the machine
+    /// isn't run and gets the worst fitness, `f64::NEG_INFINITY`.
+    pub fn evaluate<MF, F, IH, IntH, FloatH>(&mut self, mut new_machine: MF, mut fitness: F)
+        where MF: FnMut() -> Machine<SimpleInstruction, IH, IntH, FloatH>,
+              F: FnMut(&mut Machine<SimpleInstruction, IH, IntH, FloatH>) -> f64,
+              IH: FnMut() -> SimpleInstruction,
+              IntH: FnMut() -> i64,
+              FloatH: FnMut() -> f64
+    {
+        for individual in &mut self.individuals {
+            let mut machine = new_machine();
+            individual.fitness = Some(if machine.provide((&individual.chromosome).into()) {
+                fitness(&mut machine)
+            } else {
+                f64::NEG_INFINITY
+            });
+            individual.cases.clear();
+        }
+    }
+
+    /// Evaluate every individual on each of `cases` test cases, loading it into a fresh `Machine` from
+    /// `new_machine` for every case. `fitness` is given the index of the case and scores the individual on it.
+    ///
+    /// The individual's overall fitness is the sum of its case fitnesses. A case whose machine the individual is
+    /// too large to fit in isn't run and gets the worst fitness, `f64::NEG_INFINITY`.
+    pub fn evaluate_cases<MF, F, IH, IntH, FloatH>(&mut self, cases: usize, mut new_machine: MF, mut fitness: F)
+        where MF: FnMut() -> Machine<SimpleInstruction, IH, IntH, FloatH>,
+              F: FnMut(usize, &mut Machine<SimpleInstruction, IH, IntH, FloatH>) -> f64,
+              IH: FnMut() -> SimpleInstruction,
+              IntH: FnMut() -> i64,
+              FloatH: FnMut() -> f64
+    {
+        for individual in &mut self.individuals {
+            let chromosome = &individual.chromosome;
+            individual.cases = (0..cases)
+                .map(|case| {
+                    let mut machine = new_machine();
+                    if machine.provide(chromosome.into()) {
+                        fitness(case, &mut machine)
+                    } else {
+                        f64::NEG_INFINITY
+                    }
+                })
+                .collect();
+            individual.fitness = Some(individual.cases.iter().sum());
+        }
+    }
+
+    /// Replace the individuals with the next generation bred from their current fitness.
+    ///
+    /// The fittest `elitism` individuals are kept as-is, and the rest are children of parents picked by `selection`
+    /// and combined by `crossover`. Every individual in the new generation is unevaluated.
+    pub fn next_generation<S, C>(&mut self, selection: &S, crossover: &C)
+        where S: Selection,
+              C: Crossover
+    {
+        let size = self.individuals.len();
+        if size == 0 {
+            return;
+        }
+        let exp = Exp::new(1.0 / self.config.mutation_spacing);
+
+        let ranked = rank(&self.individuals);
+        let mut next = ranked
+            .iter()
+            .take(self.config.elitism)
+            .map(|&i| Individual::new(self.individuals[i].chromosome.clone()))
+            .collect::<Vec<_>>();
+
+        while next.len() < size {
+            let a = selection.select(&self.individuals, &ranked, &mut self.rng);
+            let mut child = if self.rng.gen::<f64>() < self.config.crossover_rate {
+                let b = selection.select(&self.individuals, &ranked, &mut self.rng);
+                crossover.cross(&self.individuals[a].chromosome, &self.individuals[b].chromosome, &mut self.rng)
+            } else {
+                self.individuals[a].chromosome.clone()
+            };
+            if self.rng.gen::<f64>() < self.config.mutation_rate {
+                child.mutate(self.config.max_mutations, &exp, &self.config.instructions, &mut self.rng);
+            }
+            next.push(Individual::new(child));
+        }
+
+        self.individuals = next;
+        self.generation += 1;
+    }
+
+    /// Evaluate and breed for `generations` generations, then evaluate the final generation.
+    pub fn evolve<S, C, MF, F, IH, IntH, FloatH>(&mut self,
+                                                 generations: usize,
+                                                 selection: &S,
+                                                 crossover: &C,
+                                                 mut new_machine: MF,
+                                                 mut fitness: F)
+        where S: Selection,
+              C: Crossover,
+              MF: FnMut() -> Machine<SimpleInstruction, IH, IntH, FloatH>,
+              F: FnMut(&mut Machine<SimpleInstruction, IH, IntH, FloatH>) -> f64,
+              IH: FnMut() -> SimpleInstruction,
+              IntH: FnMut() -> i64,
+              FloatH: FnMut() -> f64
+    {
+        for _ in 0..generations {
+            self.evaluate(&mut new_machine, &mut fitness);
+            self.next_generation(selection, crossover);
+        }
+        self.evaluate(&mut new_machine, &mut fitness);
+    }
+
+    /// The fittest individual, if there are any.
+    pub fn best(&self) -> Option<&Individual> {
+        self.individuals
+            .iter()
+            .fold(None, |best: Option<&Individual>, i| match best {
+                Some(b) if b.score() >= i.score() => Some(b),
+                _ => Some(i),
+            })
+    }
+}
@@ -0,0 +1,139 @@
+use super::Individual;
+
+use std::f64;
+use rand::Rng;
+
+/// A strategy for picking parents out of a scored generation.
+pub trait Selection {
+    /// Pick the index of one parent from `individuals`, which must not be empty.
+    ///
+    /// `ranked` holds the indices of `individuals` from the fittest to the least fit, as produced by `rank`, so that
+    /// the generation only has to be sorted once however many parents are picked from it.
+    fn select<R: Rng>(&self, individuals: &[Individual], ranked: &[usize], rng: &mut R) -> usize;
+}
+
+/// The indices of `individuals` ordered from the fittest to the least fit.
+pub fn rank(individuals: &[Individual]) -> Vec<usize> {
+    let mut ranked = (0..individuals.len()).collect::<Vec<_>>();
+    ranked.sort_by(|&a, &b| individuals[b].score().partial_cmp(&individuals[a].score()).unwrap());
+    ranked
+}
+
+/// Pick the fittest of `size` individuals chosen uniformly at random.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tournament {
+    pub size: usize,
+}
+
+impl Selection for Tournament {
+    fn select<R: Rng>(&self, individuals: &[Individual], _: &[usize], rng: &mut R) -> usize {
+        let mut best = rng.gen_range(0, individuals.len());
+        for _ in 1..self.size {
+            let challenger = rng.gen_range(0, individuals.len());
+            if individuals[challenger].score() > individuals[best].score() {
+                best = challenger;
+            }
+        }
+        best
+    }
+}
+
+/// Pick individuals with a chance proportional to how much their fitness exceeds the worst evaluated fitness.
+///
+/// Unevaluated individuals are never picked unless nobody has been evaluated, in which case the choice is uniform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Roulette;
+
+impl Selection for Roulette {
+    fn select<R: Rng>(&self, individuals: &[Individual], _: &[usize], rng: &mut R) -> usize {
+        let min = individuals
+            .iter()
+            .map(Individual::score)
+            .filter(|s| s.is_finite())
+            .fold(f64::INFINITY, f64::min);
+        let weight = |i: &Individual| {
+            let s = i.score();
+            if s.is_finite() { s - min } else { 0.0 }
+        };
+        let total: f64 = individuals.iter().map(&weight).sum();
+        if total <= 0.0 || !total.is_finite() {
+            return rng.gen_range(0, individuals.len());
+        }
+        let mut point = rng.gen::<f64>() * total;
+        for (ix, i) in individuals.iter().enumerate() {
+            point -= weight(i);
+            if point < 0.0 {
+                return ix;
+            }
+        }
+        individuals.len() - 1
+    }
+}
+
+/// Pick individuals with a chance proportional to their rank, where the worst has rank 1 and the best has rank `n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rank;
+
+impl Selection for Rank {
+    fn select<R: Rng>(&self, _: &[Individual], ranked: &[usize], rng: &mut R) -> usize {
+        let n = ranked.len();
+        // Choose a rank from 1 to n with probability proportional to it, which is the smallest rank whose
+        // triangular number exceeds the point. `rank` counts from 0 here.
+        let point = rng.gen_range(0, n * (n + 1) / 2);
+        let mut rank = ((((8 * point + 1) as f64).sqrt() - 1.0) / 2.0) as usize;
+        // Correct for any rounding in the square root.
+        while rank * (rank + 1) / 2 > point {
+            rank -= 1;
+        }
+        while (rank + 1) * (rank + 2) / 2 <= point {
+            rank += 1;
+        }
+        ranked[n - 1 - rank]
+    }
+}
+
+/// Pick uniformly among the fittest `count` individuals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truncation {
+    pub count: usize,
+}
+
+impl Selection for Truncation {
+    fn select<R: Rng>(&self, _: &[Individual], ranked: &[usize], rng: &mut R) -> usize {
+        let count = self.count.max(1).min(ranked.len());
+        ranked[rng.gen_range(0, count)]
+    }
+}
+
+/// Filter the individuals through every test case in a random order, keeping only those with the best result on
+/// each case, then pick uniformly among the survivors.
+///
+/// This uses `Individual::cases`, so the population must be scored with `Population::evaluate_cases`. A case result
+/// is considered best if it is within `epsilon` of the best result among the remaining individuals, so an `epsilon`
+/// of `0.0` gives standard lexicase selection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lexicase {
+    pub epsilon: f64,
+}
+
+impl Selection for Lexicase {
+    fn select<R: Rng>(&self, individuals: &[Individual], _: &[usize], rng: &mut R) -> usize {
+        let cases = individuals.iter().map(|i| i.cases.len()).max().unwrap_or(0);
+        let mut order = (0..cases).collect::<Vec<_>>();
+        rng.shuffle(&mut order);
+
+        let case_score = |ix: usize, case: usize| match individuals[ix].cases.get(case) {
+            Some(&s) if !s.is_nan() => s,
+            _ => f64::NEG_INFINITY,
+        };
+        let mut candidates = (0..individuals.len()).collect::<Vec<_>>();
+        for case in order {
+            if candidates.len() == 1 {
+                break;
+            }
+            let best = candidates.iter().map(|&ix| case_score(ix, case)).fold(f64::NEG_INFINITY, f64::max);
+            candidates.retain(|&ix| case_score(ix, case) >= best - self.epsilon);
+        }
+        candidates[rng.gen_range(0, candidates.len())]
+    }
+}
@@ -1,286 +1,10 @@
-use {Instruction, Machine};
+use {Fault, Instruction, Machine, State};
+use super::PlainOp;
+use mem::TotalMemory;
 use heapsize::HeapSizeOf;
-use rand;
 use vec::*;
-use std::mem;
-
-/// Instructions which have implicit parameters and are encodable with a single integer.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub enum PlainOp {
-    // Integer operations
-    /// integer: (a -- a++)
-    Inci64,
-    /// integer: (a -- a--)
-    Deci64,
-    /// integer: (a b -- (a + b))
-    Addi64,
-    /// integer: (a b -- (a - b))
-    Subi64,
-    /// integer: (a b -- (a * b))
-    Muli64,
-    /// integer: (a b -- (a / b))
-    Divi64,
-    /// integer: (a b -- (a % b))
-    Remi64,
-    /// integer: (a -- -a)
-    Negi64,
-    /// integer: (a -- |a|)
-    Absi64,
-    /// integer: (a -- |a|)
-    Powi64,
-    /// integer: (a b -- (a << b))
-    Rotli64,
-    /// integer: (a b -- (a >> b))
-    Rotri64,
-    /// integer: (a b -- (a << b))
-    Shftli64,
-    /// integer: (a b -- (a >> b))
-    Shftri64,
-    /// integer: (a b -- (a & b))
-    Andi64,
-    /// integer: (a b -- (a | b))
-    Ori64,
-    /// integer: (a b -- (a ^ b))
-    Xori64,
-    /// integer: (a -- ~a)
-    Invi64,
-    /// integer: (a b -- )
-    /// bool: ( -- a < b)
-    Lesi64,
-    /// integer: (a b -- )
-    /// bool: ( -- a > b)
-    Grti64,
-    /// integer: (a b -- )
-    /// bool: ( -- a = b)
-    Eqi64,
-    /// integer: (a b -- )
-    /// bool: ( -- a != b)
-    Neqi64,
-
-    // Floating point operations
-    /// float: (a -- a++)
-    Incf64,
-    /// float: (a -- a--)
-    Decf64,
-    /// float: (a b -- (a + b))
-    Addf64,
-    /// float: (a b -- (a - b))
-    Subf64,
-    /// float: (a b -- (a * b))
-    Mulf64,
-    /// float: (a b -- (a / b))
-    Divf64,
-    /// float: (a b -- (a % b))
-    Remf64,
-    /// float: (a -- -a)
-    Negf64,
-    /// float: (a -- |a|)
-    Absf64,
-    /// float: (a -- a**b)
-    /// int: (b -- )
-    Powif64,
-    /// float: (a b -- a**b)
-    Powff64,
-    /// float: (a b -- )
-    /// bool: ( -- a < b)
-    Lesf64,
-    /// float: (a b -- )
-    /// bool: ( -- a > b)
-    Grtf64,
-    /// float: (a b -- )
-    /// bool: ( -- a = b)
-    Eqf64,
-    /// float: (a b -- )
-    /// bool: ( -- a != b)
-    Neqf64,
-
-    // Boolean operations
-    /// bool: (a b -- a && b)
-    Andb,
-    /// bool: (a b -- a || b)
-    Orb,
-    /// bool: (a b -- a == b)
-    Eqb,
-    /// bool: (a b -- a != b)
-    Neqb,
-    /// bool: (a -- !a)
-    Notb,
-
-    // Conversion operations
-    /// int: (a -- )
-    /// float: ( -- a)
-    Itof,
-    /// float: (a -- )
-    /// int: ( -- a)
-    Ftoi,
-
-    // Stack manipulation
-    /// int: (b -- )
-    /// ins: (a b.. -- b.. a)
-    Rotins,
-    /// int: (a b.. b -- b.. a)
-    Roti64,
-    /// int: (b -- )
-    /// float: (a b.. -- b.. a)
-    Rotf64,
-    /// int: (b -- )
-    /// bool: (a b.. -- b.. a)
-    Rotb,
-    /// int: (b -- )
-    /// ins vec: (a b.. -- b.. a)
-    Rotinsv,
-    /// int: (b -- )
-    /// int vec: (a b.. -- b.. a)
-    Roti64v,
-    /// int: (b -- )
-    /// float vec: (a b.. -- b.. a)
-    Rotf64v,
-    /// int: (b -- )
-    /// ins: (a b.. -- a b.. a)
-    Copyins,
-    /// int: (a b.. b -- a b.. a)
-    Copyi64,
-    /// int: (b -- )
-    /// float: (a b.. -- a b.. a)
-    Copyf64,
-    /// int: (b -- )
-    /// bool: (a b.. -- a b.. a)
-    Copyb,
-    /// int: (b -- )
-    /// ins vec: (a b.. -- a b.. a)
-    Copyinsv,
-    /// int: (b -- )
-    /// int vec: (a b.. -- a b.. a)
-    Copyi64v,
-    /// int: (b -- )
-    /// float vec: (a b.. -- a b.. a)
-    Copyf64v,
-    /// ins: (a -- )
-    Popins,
-    /// int: (a -- )
-    Popi64,
-    /// float: (a -- )
-    Popf64,
-    /// bool: (a -- )
-    Popb,
-    /// ins vec: (a -- )
-    Popinsv,
-    /// int vec: (a -- )
-    Popi64v,
-    /// float vec: (a -- )
-    Popf64v,
-
-    // Vector Operations
-    /// ins: (a -- )
-    /// ins vec: (v -- a:v)
-    Pushvins,
-    /// int: (a -- )
-    /// int vec: (v -- a:v)
-    Pushvi64,
-    /// float: (a -- )
-    /// float vec: (v -- a:v)
-    Pushvf64,
-    /// ins vec: (_@(h:t) -- t)
-    /// ins: ( -- h)
-    Popvins,
-    /// int vec: (_@(h:t) -- t)
-    /// int: ( -- h)
-    Popvi64,
-    /// float vec: (_@(h:t) -- t)
-    /// float: ( -- h)
-    Popvf64,
-    /// int: (i -- )
-    /// ins vec: (v -- )
-    /// ins: ( -- v[i])
-    Readvins,
-    /// int: (i -- v[i])
-    /// int vec: (v -- )
-    Readvi64,
-    /// int: (i -- )
-    /// float vec: (v -- )
-    /// float: ( -- v[i])
-    Readvf64,
-    /// int: (i -- )
-    /// ins: (e -- )
-    /// ins vec: (v -- v)
-    /// v[i] = e
-    Writevins,
-    /// int: (e i -- )
-    /// int vec: (v -- v)
-    /// v[i] = e
-    Writevi64,
-    /// int: (i -- )
-    /// float: (e -- )
-    /// float vec: (v -- v)
-    /// v[i] = e
-    Writevf64,
-
-    // Auxiliary operations
-    /// int: ( -- 0)
-    Zeroi64,
-
-    // Instruction construction
-    /// int: (n -- )
-    /// ins: ( -- PlainOp)
-    CreatePlain,
-    /// ins vec: (b -- )
-    /// ins: ( -- BasicBlock(b))
-    CreateBasicBlock,
-    /// ins vec: (b -- )
-    /// ins: ( -- Loop(b))
-    CreateLoop,
-    /// ins vec: (t f -- )
-    /// ins: ( -- If(f, t))
-    CreateIf,
-    /// int: (n -- )
-    /// ins: ( -- Pushi64(n))
-    CreatePushi64,
-    /// float: (n -- )
-    /// ins: ( -- Pushf64(n))
-    CreatePushf64,
-    /// bool: (b -- )
-    /// ins: ( -- Pushb(b))
-    CreatePushb,
-    /// int vec: (v -- )
-    /// ins: ( -- Pushi64v(v))
-    CreatePushi64v,
-    /// float vec: (v -- )
-    /// ins: ( -- Pushf64v(v))
-    CreatePushf64v,
-
-    // Execution control
-    /// exe: (e -- )
-    Return,
-    /// exe: (e -- )
-    /// ins: ( -- e)
-    Yield,
-    /// ins: (e -- )
-    /// exe: ( -- e)
-    Call,
-    /// Does nothing
-    Nop,
-
-    // External communication
-    /// ins: (i -- )
-    Provide,
-}
-
-const TOTAL_PLAIN_INSTRUCTIONS: usize = 92;
-
-impl rand::Rand for PlainOp {
-    fn rand<R: rand::Rng>(rng: &mut R) -> Self {
-        // NOTE: Change whenever PlainOp is changed.
-        // TODO: Switch to proc macros 1.1 framework when compiler plugin is developed.
-        unsafe { mem::transmute(rng.gen_range(0u8, TOTAL_PLAIN_INSTRUCTIONS as u8)) }
-    }
-}
-
-impl HeapSizeOf for PlainOp {
-    fn heap_size_of_children(&self) -> usize {
-        0
-    }
-}
+use std::iter::once;
+use std::convert::TryFrom;
 
 #[derive(Debug, Clone)]
 pub enum SimpleInstruction {
@@ -288,11 +12,31 @@ pub enum SimpleInstruction {
     BasicBlock(TrackedIter<SimpleInstruction>),
     Loop(TrackedCycleIter<SimpleInstruction>),
     If(TrackedIter<SimpleInstruction>, TrackedIter<SimpleInstruction>),
+    /// Runs its body a number of times popped from the int stack, pushing the index of each iteration to the int
+    /// stack first if the flag is set. Once started, this holds the index of the iteration about to run and the number
+    /// of iterations.
+    DoTimes(TrackedVec<SimpleInstruction>, bool, Option<(i64, i64)>),
+    /// Pops `start end` from the int stack and runs its body once for each index from `start` to `end` inclusive,
+    /// pushing the index to the int stack before each iteration. Once started, this holds the index of the iteration
+    /// about to run and `end`.
+    DoRange(TrackedVec<SimpleInstruction>, Option<(i64, i64)>),
+    /// Pops a bool and runs its body followed by itself again if it was true, so the body should leave the bool
+    /// deciding whether to run another iteration.
+    While(TrackedVec<SimpleInstruction>),
     Pushi64(i64),
     Pushf64(f64),
     Pushb(bool),
     Pushi64v(TrackedVec<i64>),
     Pushf64v(TrackedVec<f64>),
+    Pushs(String),
+    Pushc(char),
+    Pushbv(TrackedVec<bool>),
+    /// Runs the instruction the name is bound to, or pushes the name to the name stack if it isn't bound.
+    Name(u32),
+    /// Stores the next instruction on the exe stack under the tag instead of running it.
+    Tag(u32),
+    /// Runs the instruction stored under the closest tag, as found by `State::tagged`.
+    TagRef(u32),
 }
 
 impl HeapSizeOf for SimpleInstruction {
@@ -303,11 +47,20 @@ impl HeapSizeOf for SimpleInstruction {
             BasicBlock(ref b) => b.heap_size_of_children(),
             Loop(ref l) => l.heap_size_of_children(),
             If(ref b0, ref b1) => b0.heap_size_of_children() + b1.heap_size_of_children(),
+            DoTimes(ref b, _, _) => b.heap_size_of_children(),
+            DoRange(ref b, _) => b.heap_size_of_children(),
+            While(ref b) => b.heap_size_of_children(),
             Pushi64(_) => 0,
             Pushf64(_) => 0,
             Pushb(_) => 0,
             Pushi64v(ref v) => v.heap_size_of_children(),
             Pushf64v(ref v) => v.heap_size_of_children(),
+            Pushs(ref s) => s.heap_size_of_children(),
+            Pushc(_) => 0,
+            Pushbv(ref v) => v.heap_size_of_children(),
+            Name(_) => 0,
+            Tag(_) => 0,
+            TagRef(_) => 0,
         }
     }
 }
@@ -317,825 +70,1429 @@ impl<IH, IntH, FloatH> Instruction<IH, IntH, FloatH> for SimpleInstruction
           IntH: FnMut() -> i64,
           FloatH: FnMut() -> f64
 {
-    fn operate(self, machine: &mut Machine<Self, IH, IntH, FloatH>) -> (Option<Self>, bool) {
-        use self::SimpleInstruction::*;
-        use self::PlainOp::*;
-        // The returned instruction, which most operations don't use.
-        let mut ret_ins = None;
-        let success = match self {
-            PlainOp(Inci64) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a.wrapping_add(1)).is_ok()
-            }
-            PlainOp(Deci64) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a.wrapping_sub(1)).is_ok()
-            }
-            PlainOp(Addi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a.wrapping_add(b)).is_ok()
-            }
-            PlainOp(Subi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a.wrapping_sub(b)).is_ok()
-            }
-            PlainOp(Muli64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a.wrapping_mul(b)).is_ok()
-            }
-            PlainOp(Divi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_div(b).unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Remi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_rem(b).unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Negi64) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_neg().unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Absi64) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_abs().unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Powi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.pow((b.abs() & (0x7FFFFFFF)) as u32))
-                    .is_ok()
-            }
-            PlainOp(Rotli64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.rotate_left((b & (0x7FFFFFFF)) as u32))
-                    .is_ok()
-            }
-            PlainOp(Rotri64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.rotate_right((b & (0x7FFFFFFF)) as u32))
-                    .is_ok()
-            }
-            PlainOp(Shftli64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_shl((b & (0x7FFFFFFF)) as u32)
-                                  .unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Shftri64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .push_int(a.checked_shr((b & (0x7FFFFFFF)) as u32)
-                                  .unwrap_or_else(&mut machine.int_handler))
-                    .is_ok()
-            }
-            PlainOp(Andi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a & b).is_ok()
-            }
-            PlainOp(Ori64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a | b).is_ok()
-            }
-            PlainOp(Xori64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(a ^ b).is_ok()
-            }
-            PlainOp(Invi64) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_int(!a).is_ok()
-            }
-            PlainOp(Lesi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_bool(a < b).is_ok()
-            }
-            PlainOp(Grti64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_bool(a > b).is_ok()
-            }
-            PlainOp(Eqi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_bool(a == b).is_ok()
-            }
-            PlainOp(Neqi64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_bool(a != b).is_ok()
-            }
-            PlainOp(Incf64) => {
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a + 1.0).is_ok()
-            }
-            PlainOp(Decf64) => {
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a - 1.0).is_ok()
-            }
-            PlainOp(Addf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a + b).is_ok()
-            }
-            PlainOp(Subf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a - b).is_ok()
-            }
-            PlainOp(Mulf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a * b).is_ok()
-            }
-            PlainOp(Divf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a / b).is_ok()
-            }
-            PlainOp(Remf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a % b).is_ok()
-            }
-            PlainOp(Negf64) => {
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(-a).is_ok()
-            }
-            PlainOp(Absf64) => {
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a.abs()).is_ok()
-            }
-            PlainOp(Powif64) => {
-                let b = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine
-                    .state
-                    .push_float(a.powi(if b <= i32::max_value() as i64 &&
-                                          b >= i32::min_value as i64 {
-                                           b as i32
-                                       } else {
-                                           1
-                                       }))
-                    .is_ok()
-            }
-            PlainOp(Powff64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_float(a.powf(b)).is_ok()
-            }
-            PlainOp(Lesf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_bool(a < b).is_ok()
-            }
-            PlainOp(Grtf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_bool(a > b).is_ok()
-            }
-            PlainOp(Eqf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_bool(a == b).is_ok()
-            }
-            PlainOp(Neqf64) => {
-                let b = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine.state.push_bool(a != b).is_ok()
-            }
-            PlainOp(Andb) => {
-                let b = machine.state.pop_bool().unwrap_or(false);
-                let a = machine.state.pop_bool().unwrap_or(false);
-                machine.state.push_bool(a && b).is_ok()
-            }
-            PlainOp(Orb) => {
-                let b = machine.state.pop_bool().unwrap_or(false);
-                let a = machine.state.pop_bool().unwrap_or(false);
-                machine.state.push_bool(a || b).is_ok()
-            }
-            PlainOp(Eqb) => {
-                let b = machine.state.pop_bool().unwrap_or(false);
-                let a = machine.state.pop_bool().unwrap_or(false);
-                machine.state.push_bool(a == b).is_ok()
-            }
-            PlainOp(Neqb) => {
-                let b = machine.state.pop_bool().unwrap_or(false);
-                let a = machine.state.pop_bool().unwrap_or(false);
-                machine.state.push_bool(a != b).is_ok()
-            }
-            PlainOp(Notb) => {
-                let a = machine.state.pop_bool().unwrap_or(false);
-                machine.state.push_bool(!a).is_ok()
-            }
-            PlainOp(Itof) => {
-                let a = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.push_float(a as f64).is_ok()
-            }
-            PlainOp(Ftoi) => {
-                use std::num::FpCategory;
-                let a = machine
-                    .state
-                    .pop_float()
-                    .unwrap_or_else(&mut machine.float_handler);
-                machine
-                    .state
-                    .push_int(match a.classify() {
-                                  FpCategory::Normal => a as i64,
-                                  FpCategory::Zero => 0,
-                                  _ => (machine.int_handler)(),
-                              })
-                    .is_ok()
-            }
-            PlainOp(Rotins) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_ins((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Roti64) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_int((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Rotf64) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_float((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Rotb) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_bool((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Rotinsv) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_ins_vec((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Roti64v) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_int_vec((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Rotf64v) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine.state.rot_float_vec((pos & 0x7FFFFFFF) as usize)
-            }
-            PlainOp(Copyins) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_ins((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_ins(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyi64) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_int((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_int(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyf64) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_float((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_float(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyb) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_bool((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_bool(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyinsv) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_ins_vec((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_ins_vec(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyi64v) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_int_vec((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_int_vec(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Copyf64v) => {
-                let pos = machine
-                    .state
-                    .pop_int()
-                    .unwrap_or_else(&mut machine.int_handler);
-                machine
-                    .state
-                    .copy_float_vec((pos & 0x7FFFFFFF) as usize)
-                    .and_then(|copy| machine.state.push_float_vec(copy).ok())
-                    .is_some()
-            }
-            PlainOp(Popins) => machine.state.pop_ins().is_some(),
-            PlainOp(Popi64) => machine.state.pop_int().is_some(),
-            PlainOp(Popf64) => machine.state.pop_float().is_some(),
-            PlainOp(Popb) => machine.state.pop_bool().is_some(),
-            PlainOp(Popinsv) => machine.state.pop_ins_vec().is_some(),
-            PlainOp(Popi64v) => machine.state.pop_int_vec().is_some(),
-            PlainOp(Popf64v) => machine.state.pop_float_vec().is_some(),
-            PlainOp(Pushvins) => {
-                machine
-                    .state
-                    .pop_ins()
-                    .and_then(|e| machine.state.push_ins_to_vec(e).ok())
-                    .unwrap_or(false)
-            }
-            PlainOp(Pushvi64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|e| machine.state.push_int_to_vec(e).ok())
-                    .unwrap_or(false)
-            }
-            PlainOp(Pushvf64) => {
-                machine
-                    .state
-                    .pop_float()
-                    .and_then(|e| machine.state.push_float_to_vec(e).ok())
-                    .unwrap_or(false)
-            }
-            PlainOp(Popvins) => machine.state.pop_ins_from_vec().is_some(),
-            PlainOp(Popvi64) => machine.state.pop_int_from_vec().is_some(),
-            PlainOp(Popvf64) => machine.state.pop_float_from_vec().is_some(),
-            PlainOp(Readvins) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| {
-                                  machine
-                                      .state
-                                      .get_ins_from_vec((ix & 0x7FFFFFFF) as usize)
-                              })
-                    .and_then(|e| machine.state.push_ins(e).ok())
-                    .is_some()
-            }
-            PlainOp(Readvi64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| {
-                                  machine
-                                      .state
-                                      .get_int_from_vec((ix & 0x7FFFFFFF) as usize)
-                              })
-                    .and_then(|e| machine.state.push_int(e).ok())
-                    .is_some()
-            }
-            PlainOp(Readvf64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| {
-                                  machine
-                                      .state
-                                      .get_float_from_vec((ix & 0x7FFFFFFF) as usize)
-                              })
-                    .and_then(|e| machine.state.push_float(e).ok())
-                    .is_some()
-            }
-            PlainOp(Writevins) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| machine.state.pop_ins().map(|e| (ix, e)))
-                    .and_then(|(ix, e)| {
-                                  machine
-                                      .state
-                                      .write_ins_to_vec((ix & 0x7FFFFFFF) as usize, e)
-                                      .ok()
-                              })
-                    .unwrap_or(false)
-            }
-            PlainOp(Writevi64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| machine.state.pop_int().map(|e| (ix, e)))
-                    .map_or(false, |(ix, e)| {
-                        machine
-                            .state
-                            .write_int_to_vec((ix & 0x7FFFFFFF) as usize, e);
-                        true
-                    })
-            }
-            PlainOp(Writevf64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .and_then(|ix| machine.state.pop_float().map(|e| (ix, e)))
-                    .map_or(false, |(ix, e)| {
-                        machine
-                            .state
-                            .write_float_to_vec((ix & 0x7FFFFFFF) as usize, e);
-                        true
-                    })
-            }
-            PlainOp(Zeroi64) => machine.state.push_int(0).is_ok(),
-            PlainOp(CreatePlain) => {
-                machine
-                    .state
-                    .pop_int()
-                    .map(|n| (n & 0x7FFFFFFF) as usize)
-                    .and_then(|n| if n < TOTAL_PLAIN_INSTRUCTIONS {
-                                  Some(unsafe { mem::transmute(n as u8) })
-                              } else {
-                                  None
-                              })
-                    .map(PlainOp)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
-            }
-            PlainOp(CreateBasicBlock) => {
-                machine
-                    .state
-                    .pop_ins_vec()
-                    .map(|v| BasicBlock(v.into_iter()))
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
-            }
-            PlainOp(CreateLoop) => {
-                machine
-                    .state
-                    .pop_ins_vec()
-                    .map(|v| Loop(v.into_cycle_iter()))
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
-            }
-            PlainOp(CreateIf) => {
-                machine
-                    .state
-                    .pop_ins_vec()
-                    .and_then(|vf| machine.state.pop_ins_vec().map(|vt| (vf, vt)))
-                    .map(|(vf, vt)| If(vf.into_iter(), vt.into_iter()))
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
-            }
-            PlainOp(CreatePushi64) => {
-                machine
-                    .state
-                    .pop_int()
-                    .map(Pushi64)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
-            }
-            PlainOp(CreatePushf64) => {
-                machine
-                    .state
-                    .pop_float()
-                    .map(Pushf64)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
+    fn operate(self, machine: &mut Machine<Self, IH, IntH, FloatH>) -> (Option<Self>, Result<(), Fault>) {
+        // Faults which a handler covered for are only reported if nothing worse happens.
+        let mut handled = None;
+        match execute(self, machine, &mut handled) {
+            Ok(provided) => (provided, handled.map_or(Ok(()), Err)),
+            Err(fault) => (None, Err(fault)),
+        }
+    }
+}
+
+/// Pop a bool, or use `false` if the stack is empty and record the underflow in `fault`.
+fn pop_bool_or_false<Ins>(state: &mut State<Ins>, fault: &mut Option<Fault>) -> bool
+    where Ins: TotalMemory
+{
+    state.pop_bool().unwrap_or_else(|| {
+        *fault = Some(Fault::Underflow);
+        false
+    })
+}
+
+/// Build a `BasicBlock` out of `instructions`, failing with `Full` as soon as it outgrows the memory left in `state`
+/// so that expanding a large vector never allocates more than the state could hold.
+fn bounded_block<I>(state: &State<SimpleInstruction>, instructions: I) -> Result<SimpleInstruction, Fault>
+    where I: IntoIterator<Item = SimpleInstruction>
+{
+    let left = state.max_size.saturating_sub(state.size());
+    let mut block = TrackedVec::new();
+    for ins in instructions {
+        block.push(ins);
+        if block.heap_size_of_children() > left {
+            return Err(Fault::Full);
+        }
+    }
+    Ok(SimpleInstruction::BasicBlock(block.into_iter()))
+}
+
+/// Copy input register `ix` out of `inputs`.
+fn input<T: Clone>(inputs: &[T], ix: usize) -> Result<T, Fault> {
+    inputs.get(ix).cloned().ok_or(Fault::OutOfRange)
+}
+
+/// Mask an integer operand into a position or index.
+fn index(n: i64) -> usize {
+    (n & 0x7FFFFFFF) as usize
+}
+
+/// Execute an instruction, returning the instruction it provides, if any.
+fn execute<IH, IntH, FloatH>(ins: SimpleInstruction,
+                             machine: &mut Machine<SimpleInstruction, IH, IntH, FloatH>,
+                             handled: &mut Option<Fault>)
+                             -> Result<Option<SimpleInstruction>, Fault>
+    where IH: FnMut() -> SimpleInstruction,
+          IntH: FnMut() -> i64,
+          FloatH: FnMut() -> f64
+{
+    use self::SimpleInstruction::*;
+    use self::PlainOp::*;
+    match ins {
+        PlainOp(Inci64) => {
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.wrapping_add(1))?;
+        }
+        PlainOp(Deci64) => {
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.wrapping_sub(1))?;
+        }
+        PlainOp(Addi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.wrapping_add(b))?;
+        }
+        PlainOp(Subi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.wrapping_sub(b))?;
+        }
+        PlainOp(Muli64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.wrapping_mul(b))?;
+        }
+        PlainOp(Divi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_div(b), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Remi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_rem(b), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Negi64) => {
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_neg(), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Absi64) => {
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_abs(), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Powi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_pow((b.wrapping_abs() & 0x7FFFFFFF) as u32), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Rotli64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.rotate_left((b & 0x7FFFFFFF) as u32))?;
+        }
+        PlainOp(Rotri64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a.rotate_right((b & 0x7FFFFFFF) as u32))?;
+        }
+        PlainOp(Shftli64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_shl((b & 0x7FFFFFFF) as u32), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Shftri64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            let r = machine.int_or_handle(a.checked_shr((b & 0x7FFFFFFF) as u32), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Andi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a & b)?;
+        }
+        PlainOp(Ori64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a | b)?;
+        }
+        PlainOp(Xori64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(a ^ b)?;
+        }
+        PlainOp(Invi64) => {
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_int(!a)?;
+        }
+        PlainOp(Lesi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_bool(a < b)?;
+        }
+        PlainOp(Grti64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_bool(a > b)?;
+        }
+        PlainOp(Eqi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_bool(a == b)?;
+        }
+        PlainOp(Neqi64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_bool(a != b)?;
+        }
+        PlainOp(Incf64) => {
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a + 1.0)?;
+        }
+        PlainOp(Decf64) => {
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a - 1.0)?;
+        }
+        PlainOp(Addf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a + b)?;
+        }
+        PlainOp(Subf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a - b)?;
+        }
+        PlainOp(Mulf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a * b)?;
+        }
+        PlainOp(Divf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a / b)?;
+        }
+        PlainOp(Remf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a % b)?;
+        }
+        PlainOp(Negf64) => {
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(-a)?;
+        }
+        PlainOp(Absf64) => {
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a.abs())?;
+        }
+        PlainOp(Powif64) => {
+            let b = machine.pop_int_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            let b = if b <= i32::MAX as i64 && b >= i32::MIN as i64 {
+                b as i32
+            } else {
+                1
+            };
+            machine.state.push_float(a.powi(b))?;
+        }
+        PlainOp(Powff64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_float(a.powf(b))?;
+        }
+        PlainOp(Lesf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_bool(a < b)?;
+        }
+        PlainOp(Grtf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_bool(a > b)?;
+        }
+        PlainOp(Eqf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_bool(a == b)?;
+        }
+        PlainOp(Neqf64) => {
+            let b = machine.pop_float_or_handle(handled);
+            let a = machine.pop_float_or_handle(handled);
+            machine.state.push_bool(a != b)?;
+        }
+        PlainOp(Andb) => {
+            let b = pop_bool_or_false(&mut machine.state, handled);
+            let a = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_bool(a && b)?;
+        }
+        PlainOp(Orb) => {
+            let b = pop_bool_or_false(&mut machine.state, handled);
+            let a = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_bool(a || b)?;
+        }
+        PlainOp(Eqb) => {
+            let b = pop_bool_or_false(&mut machine.state, handled);
+            let a = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_bool(a == b)?;
+        }
+        PlainOp(Neqb) => {
+            let b = pop_bool_or_false(&mut machine.state, handled);
+            let a = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_bool(a != b)?;
+        }
+        PlainOp(Notb) => {
+            let a = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_bool(!a)?;
+        }
+        PlainOp(Itof) => {
+            let a = machine.pop_int_or_handle(handled);
+            machine.state.push_float(a as f64)?;
+        }
+        PlainOp(Ftoi) => {
+            use std::num::FpCategory;
+            let a = machine.pop_float_or_handle(handled);
+            let r = match a.classify() {
+                FpCategory::Normal => Some(a as i64),
+                FpCategory::Zero => Some(0),
+                _ => None,
+            };
+            let r = machine.int_or_handle(r, handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Rotins) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_ins(index(pos))?;
+        }
+        PlainOp(Roti64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_int(index(pos))?;
+        }
+        PlainOp(Rotf64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_float(index(pos))?;
+        }
+        PlainOp(Rotb) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_bool(index(pos))?;
+        }
+        PlainOp(Rotinsv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_ins_vec(index(pos))?;
+        }
+        PlainOp(Roti64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_int_vec(index(pos))?;
+        }
+        PlainOp(Rotf64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_float_vec(index(pos))?;
+        }
+        PlainOp(Copyins) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_ins(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_ins(copy)?;
+        }
+        PlainOp(Copyi64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_int(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_int(copy)?;
+        }
+        PlainOp(Copyf64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_float(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_float(copy)?;
+        }
+        PlainOp(Copyb) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_bool(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_bool(copy)?;
+        }
+        PlainOp(Copyinsv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_ins_vec(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_ins_vec(copy)?;
+        }
+        PlainOp(Copyi64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_int_vec(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_int_vec(copy)?;
+        }
+        PlainOp(Copyf64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_float_vec(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_float_vec(copy)?;
+        }
+        PlainOp(Popins) => {
+            machine.state.pop_ins().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popi64) => {
+            machine.state.pop_int().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popf64) => {
+            machine.state.pop_float().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popb) => {
+            machine.state.pop_bool().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popinsv) => {
+            machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popi64v) => {
+            machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popf64v) => {
+            machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Pushvins) => {
+            let e = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            machine.state.push_ins_to_vec(e)?;
+        }
+        PlainOp(Pushvi64) => {
+            let e = machine.state.pop_int().ok_or(Fault::Empty)?;
+            machine.state.push_int_to_vec(e)?;
+        }
+        PlainOp(Pushvf64) => {
+            let e = machine.state.pop_float().ok_or(Fault::Empty)?;
+            machine.state.push_float_to_vec(e)?;
+        }
+        PlainOp(Popvins) => {
+            machine.state.pop_ins_from_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popvi64) => {
+            machine.state.pop_int_from_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popvf64) => {
+            machine.state.pop_float_from_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Readvins) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.get_ins_from_vec(index(ix))?;
+            machine.state.push_ins(e)?;
+        }
+        PlainOp(Readvi64) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.get_int_from_vec(index(ix))?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(Readvf64) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.get_float_from_vec(index(ix))?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(Writevins) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            machine.state.write_ins_to_vec(index(ix), e)?;
+        }
+        PlainOp(Writevi64) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.pop_int().ok_or(Fault::Empty)?;
+            machine.state.write_int_to_vec(index(ix), e)?;
+        }
+        PlainOp(Writevf64) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.pop_float().ok_or(Fault::Empty)?;
+            machine.state.write_float_to_vec(index(ix), e)?;
+        }
+        PlainOp(Zeroi64) => machine.state.push_int(0)?,
+        PlainOp(CreatePlain) => {
+            let n = index(machine.state.pop_int().ok_or(Fault::Empty)?);
+            let op = u16::try_from(n).ok().and_then(self::PlainOp::from_code).ok_or(Fault::InvalidOpcode)?;
+            machine.state.push_ins(PlainOp(op))?;
+        }
+        PlainOp(CreateBasicBlock) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(BasicBlock(v.into_iter()))?;
+        }
+        PlainOp(CreateLoop) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Loop(v.into_cycle_iter()))?;
+        }
+        PlainOp(CreateIf) => {
+            let vf = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            let vt = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(If(vf.into_iter(), vt.into_iter()))?;
+        }
+        PlainOp(CreatePushi64) => {
+            let e = machine.state.pop_int().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushi64(e))?;
+        }
+        PlainOp(CreatePushf64) => {
+            let e = machine.state.pop_float().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushf64(e))?;
+        }
+        PlainOp(CreatePushb) => {
+            let e = machine.state.pop_bool().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushb(e))?;
+        }
+        PlainOp(CreatePushi64v) => {
+            let e = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushi64v(e))?;
+        }
+        PlainOp(CreatePushf64v) => {
+            let e = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushf64v(e))?;
+        }
+        PlainOp(Return) => {
+            machine.state.pop_exe().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Yield) => {
+            let e = machine.state.pop_exe().ok_or(Fault::Empty)?;
+            machine.state.push_ins(e)?;
+        }
+        PlainOp(Call) => {
+            let e = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            machine.state.push_exe(e)?;
+        }
+        PlainOp(Nop) => {}
+        PlainOp(Provide) => return machine.state.pop_ins().ok_or(Fault::Empty).map(Some),
+        PlainOp(Ini64) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.ints, index(ix))?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(Inf64) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.floats, index(ix))?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(Inb) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.bools, index(ix))?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(Ini64v) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.int_vecs, index(ix))?;
+            machine.state.push_int_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(Inf64v) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.float_vecs, index(ix))?;
+            machine.state.push_float_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(Concats) => {
+            let b = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let mut a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            a.push_str(&b);
+            machine.state.push_string(a)?;
+        }
+        PlainOp(Lens) => {
+            let s = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_int(s.chars().count() as i64)?;
+        }
+        PlainOp(Substrs) => {
+            let end = index(machine.pop_int_or_handle(handled));
+            let start = index(machine.pop_int_or_handle(handled));
+            let s = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let sub = s.chars().skip(start).take(end.saturating_sub(start)).collect();
+            machine.state.push_string(sub)?;
+        }
+        PlainOp(Indexofs) => {
+            let p = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let s = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let ix = s.find(&p[..]).map_or(-1, |byte| s[..byte].chars().count() as i64);
+            machine.state.push_int(ix)?;
+        }
+        PlainOp(Splits) => {
+            let p = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let s = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let parts = if p.is_empty() {
+                s.split_whitespace().map(String::from).collect::<Vec<_>>()
+            } else {
+                s.split(&p[..]).map(String::from).collect()
+            };
+            // Check that every part fits first so that running out of memory doesn't leave only some of them.
+            let size = parts.iter().map(TotalMemory::total_memory).sum::<usize>();
+            if size > machine.state.max_size.saturating_sub(machine.state.size()) {
+                return Err(Fault::Full);
+            }
+            parts.into_iter().rev().try_for_each(|part| machine.state.push_string(part))?;
+        }
+        PlainOp(Charats) => {
+            let ix = index(machine.pop_int_or_handle(handled));
+            let c = machine.state.get_top_string().ok_or(Fault::Empty)?.chars().nth(ix).ok_or(Fault::OutOfRange)?;
+            machine.state.push_char(c)?;
+        }
+        PlainOp(Less) => {
+            let b = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a < b)?;
+        }
+        PlainOp(Grts) => {
+            let b = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a > b)?;
+        }
+        PlainOp(Eqs) => {
+            let b = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a == b)?;
+        }
+        PlainOp(Neqs) => {
+            let b = machine.state.pop_string().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a != b)?;
+        }
+        PlainOp(Ctos) => {
+            let c = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_string(c.to_string())?;
+        }
+        PlainOp(Itoc) => {
+            let n = machine.pop_int_or_handle(handled);
+            let c = ::std::char::from_u32(index(n) as u32).ok_or(Fault::OutOfRange)?;
+            machine.state.push_char(c)?;
+        }
+        PlainOp(Ctoi) => {
+            let c = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_int(c as i64)?;
+        }
+        PlainOp(Lesc) => {
+            let b = machine.state.pop_char().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a < b)?;
+        }
+        PlainOp(Grtc) => {
+            let b = machine.state.pop_char().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a > b)?;
+        }
+        PlainOp(Eqc) => {
+            let b = machine.state.pop_char().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a == b)?;
+        }
+        PlainOp(Neqc) => {
+            let b = machine.state.pop_char().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_bool(a != b)?;
+        }
+        PlainOp(Rots) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_string(index(pos))?;
+        }
+        PlainOp(Rotc) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_char(index(pos))?;
+        }
+        PlainOp(Copys) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_string(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_string(copy)?;
+        }
+        PlainOp(Copyc) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_char(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_char(copy)?;
+        }
+        PlainOp(Pops) => {
+            machine.state.pop_string().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Popc) => {
+            machine.state.pop_char().ok_or(Fault::Empty)?;
+        }
+        PlainOp(CreatePushs) => {
+            let e = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushs(e))?;
+        }
+        PlainOp(CreatePushc) => {
+            let e = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushc(e))?;
+        }
+        PlainOp(Rotbv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_bool_vec(index(pos))?;
+        }
+        PlainOp(Copybv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_bool_vec(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_bool_vec(copy)?;
+        }
+        PlainOp(Popbv) => {
+            machine.state.pop_bool_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Pushvb) => {
+            let e = machine.state.pop_bool().ok_or(Fault::Empty)?;
+            machine.state.push_bool_to_vec(e)?;
+        }
+        PlainOp(Popvb) => {
+            machine.state.pop_bool_from_vec().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Readvb) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.get_bool_from_vec(index(ix))?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(Writevb) => {
+            let ix = machine.state.pop_int().ok_or(Fault::Empty)?;
+            let e = machine.state.pop_bool().ok_or(Fault::Empty)?;
+            machine.state.write_bool_to_vec(index(ix), e)?;
+        }
+        PlainOp(CreatePushbv) => {
+            let e = machine.state.pop_bool_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Pushbv(e))?;
+        }
+        PlainOp(Inbv) => {
+            let ix = machine.pop_int_or_handle(handled);
+            let e = input(&machine.inputs.bool_vecs, index(ix))?;
+            machine.state.push_bool_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(Mapvi64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| vec![Pushi64(e), f.clone(), PlainOp(Pushvi64)]);
+            let block = bounded_block(&machine.state, once(Pushi64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Filtervi64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| {
+                let keep = TrackedVec::new_from_vec(vec![Pushi64(e), PlainOp(Pushvi64)]);
+                vec![Pushi64(e), f.clone(), If(keep.into_iter(), TrackedVec::new().into_iter())]
+            });
+            let block = bounded_block(&machine.state, once(Pushi64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Foldvi64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| vec![Pushi64(e), f.clone()]);
+            let block = bounded_block(&machine.state, body)?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Zipvi64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let b = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let body = a.as_slice()
+                .iter()
+                .zip(b.as_slice())
+                .flat_map(|(&ea, &eb)| vec![Pushi64(ea), Pushi64(eb), f.clone(), PlainOp(Pushvi64)]);
+            let block = bounded_block(&machine.state, once(Pushi64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Mapvf64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| vec![Pushf64(e), f.clone(), PlainOp(Pushvf64)]);
+            let block = bounded_block(&machine.state, once(Pushf64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Filtervf64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| {
+                let keep = TrackedVec::new_from_vec(vec![Pushf64(e), PlainOp(Pushvf64)]);
+                vec![Pushf64(e), f.clone(), If(keep.into_iter(), TrackedVec::new().into_iter())]
+            });
+            let block = bounded_block(&machine.state, once(Pushf64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Foldvf64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let body = v.as_slice().iter().flat_map(|&e| vec![Pushf64(e), f.clone()]);
+            let block = bounded_block(&machine.state, body)?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Zipvf64) => {
+            let f = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            let b = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let body = a.as_slice()
+                .iter()
+                .zip(b.as_slice())
+                .flat_map(|(&ea, &eb)| vec![Pushf64(ea), Pushf64(eb), f.clone(), PlainOp(Pushvf64)]);
+            let block = bounded_block(&machine.state, once(Pushf64v(TrackedVec::new())).chain(body))?;
+            machine.state.push_exe(block)?;
+        }
+        PlainOp(Lenvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            machine.state.push_int(v.len() as i64)?;
+        }
+        PlainOp(Revvi64) => {
+            let mut v = machine.state.pop_int_vec().ok_or(Fault::Empty)?.into_vec();
+            v.reverse();
+            machine.state.push_int_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Catvi64) => {
+            let b = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let mut a = machine.state.pop_int_vec().ok_or(Fault::Empty)?.into_vec();
+            a.extend_from_slice(b.as_slice());
+            machine.state.push_int_vec(TrackedVec::new_from_vec(a))?;
+        }
+        PlainOp(Slicevi64) => {
+            let end = index(machine.pop_int_or_handle(handled));
+            let start = index(machine.pop_int_or_handle(handled));
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let end = end.min(v.len());
+            let start = start.min(end);
+            machine.state.push_int_vec(TrackedVec::new_from_vec(v.as_slice()[start..end].to_vec()))?;
+        }
+        PlainOp(Takevi64) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            let mut v = machine.state.pop_int_vec().ok_or(Fault::Empty)?.into_vec();
+            v.truncate(n);
+            machine.state.push_int_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Dropvi64) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let n = n.min(v.len());
+            machine.state.push_int_vec(TrackedVec::new_from_vec(v.as_slice()[n..].to_vec()))?;
+        }
+        PlainOp(Sortvi64) => {
+            let mut v = machine.state.pop_int_vec().ok_or(Fault::Empty)?.into_vec();
+            v.sort();
+            machine.state.push_int_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Sumvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            machine.state.push_int(v.as_slice().iter().fold(0i64, |acc, &e| acc.wrapping_add(e)))?;
+        }
+        PlainOp(Prodvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            machine.state.push_int(v.as_slice().iter().fold(1i64, |acc, &e| acc.wrapping_mul(e)))?;
+        }
+        PlainOp(Minvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let r = machine.int_or_handle(v.as_slice().iter().cloned().min(), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Maxvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let r = machine.int_or_handle(v.as_slice().iter().cloned().max(), handled);
+            machine.state.push_int(r)?;
+        }
+        PlainOp(Meanvi64) => {
+            let v = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let mean = if v.is_empty() {
+                None
+            } else {
+                Some(v.as_slice().iter().map(|&e| e as f64).sum::<f64>() / v.len() as f64)
+            };
+            let r = machine.float_or_handle(mean, handled);
+            machine.state.push_float(r)?;
+        }
+        PlainOp(Dotvi64) => {
+            let b = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_int_vec().ok_or(Fault::Empty)?;
+            let dot = a.as_slice()
+                .iter()
+                .zip(b.as_slice())
+                .fold(0i64, |acc, (&ea, &eb)| acc.wrapping_add(ea.wrapping_mul(eb)));
+            machine.state.push_int(dot)?;
+        }
+        PlainOp(Lenvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            machine.state.push_int(v.len() as i64)?;
+        }
+        PlainOp(Revvf64) => {
+            let mut v = machine.state.pop_float_vec().ok_or(Fault::Empty)?.into_vec();
+            v.reverse();
+            machine.state.push_float_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Catvf64) => {
+            let b = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let mut a = machine.state.pop_float_vec().ok_or(Fault::Empty)?.into_vec();
+            a.extend_from_slice(b.as_slice());
+            machine.state.push_float_vec(TrackedVec::new_from_vec(a))?;
+        }
+        PlainOp(Slicevf64) => {
+            let end = index(machine.pop_int_or_handle(handled));
+            let start = index(machine.pop_int_or_handle(handled));
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let end = end.min(v.len());
+            let start = start.min(end);
+            machine.state.push_float_vec(TrackedVec::new_from_vec(v.as_slice()[start..end].to_vec()))?;
+        }
+        PlainOp(Takevf64) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            let mut v = machine.state.pop_float_vec().ok_or(Fault::Empty)?.into_vec();
+            v.truncate(n);
+            machine.state.push_float_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Dropvf64) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let n = n.min(v.len());
+            machine.state.push_float_vec(TrackedVec::new_from_vec(v.as_slice()[n..].to_vec()))?;
+        }
+        PlainOp(Sortvf64) => {
+            let mut v = machine.state.pop_float_vec().ok_or(Fault::Empty)?.into_vec();
+            v.sort_by(f64::total_cmp);
+            machine.state.push_float_vec(TrackedVec::new_from_vec(v))?;
+        }
+        PlainOp(Sumvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            machine.state.push_float(v.as_slice().iter().sum::<f64>())?;
+        }
+        PlainOp(Prodvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            machine.state.push_float(v.as_slice().iter().product::<f64>())?;
+        }
+        PlainOp(Minvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let min = if v.is_empty() {
+                None
+            } else {
+                Some(v.as_slice().iter().cloned().fold(f64::INFINITY, f64::min))
+            };
+            let r = machine.float_or_handle(min, handled);
+            machine.state.push_float(r)?;
+        }
+        PlainOp(Maxvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let max = if v.is_empty() {
+                None
+            } else {
+                Some(v.as_slice().iter().cloned().fold(f64::NEG_INFINITY, f64::max))
+            };
+            let r = machine.float_or_handle(max, handled);
+            machine.state.push_float(r)?;
+        }
+        PlainOp(Meanvf64) => {
+            let v = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let mean = if v.is_empty() {
+                None
+            } else {
+                Some(v.as_slice().iter().sum::<f64>() / v.len() as f64)
+            };
+            let r = machine.float_or_handle(mean, handled);
+            machine.state.push_float(r)?;
+        }
+        PlainOp(Dotvf64) => {
+            let b = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_float_vec().ok_or(Fault::Empty)?;
+            let dot = a.as_slice().iter().zip(b.as_slice()).map(|(&ea, &eb)| ea * eb).sum::<f64>();
+            machine.state.push_float(dot)?;
+        }
+        PlainOp(CreateDoTimes) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(DoTimes(v, false, None))?;
+        }
+        PlainOp(CreateDoTimesIndexed) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(DoTimes(v, true, None))?;
+        }
+        PlainOp(CreateDoRange) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(DoRange(v, None))?;
+        }
+        PlainOp(CreateWhile) => {
+            let v = machine.state.pop_ins_vec().ok_or(Fault::Empty)?;
+            machine.state.push_ins(While(v))?;
+        }
+        PlainOp(Kexe) => {
+            if machine.state.exe_len() < 2 {
+                return Err(Fault::Empty);
             }
-            PlainOp(CreatePushb) => {
-                machine
-                    .state
-                    .pop_bool()
-                    .map(Pushb)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
+            let a = machine.state.pop_exe().unwrap();
+            machine.state.pop_exe();
+            machine.state.push_exe(a)?;
+        }
+        PlainOp(Sexe) => {
+            if machine.state.exe_len() < 3 {
+                return Err(Fault::Empty);
+            }
+            let a = machine.state.pop_exe().unwrap();
+            let b = machine.state.pop_exe().unwrap();
+            let c = machine.state.pop_exe().unwrap();
+            machine.state.push_exe(BasicBlock(TrackedVec::new_from_vec(vec![b, c.clone()]).into_iter()))?;
+            machine.state.push_exe(c)?;
+            machine.state.push_exe(a)?;
+        }
+        PlainOp(Yexe) => {
+            let a = machine.state.pop_exe().ok_or(Fault::Empty)?;
+            let again = TrackedVec::new_from_vec(vec![PlainOp(Yexe), a.clone()]);
+            machine.state.push_exe(BasicBlock(again.into_iter()))?;
+            machine.state.push_exe(a)?;
+        }
+        PlainOp(Dupexe) => machine.state.dup_exe()?,
+        PlainOp(Swapexe) => machine.state.swap_exe()?,
+        PlainOp(Rotexe) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_exe(index(pos))?;
+        }
+        PlainOp(Flushexe) => machine.state.flush_exe(),
+        PlainOp(DoRangeexe) => {
+            if machine.state.exe_len() < 1 {
+                return Err(Fault::Empty);
+            }
+            let end = machine.pop_int_or_handle(handled);
+            let start = machine.pop_int_or_handle(handled);
+            let a = machine.state.pop_exe().unwrap();
+            machine.state.push_exe(DoRange(TrackedVec::new_from_vec(vec![a]), Some((start, end))))?;
+        }
+        PlainOp(DoTimesexe) => {
+            if machine.state.exe_len() < 1 {
+                return Err(Fault::Empty);
             }
-            PlainOp(CreatePushi64v) => {
-                machine
-                    .state
-                    .pop_int_vec()
-                    .map(Pushi64v)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
+            let n = machine.pop_int_or_handle(handled);
+            let a = machine.state.pop_exe().unwrap();
+            if n > 0 {
+                machine.state.push_exe(DoTimes(TrackedVec::new_from_vec(vec![a]), false, Some((0, n))))?;
             }
-            PlainOp(CreatePushf64v) => {
-                machine
-                    .state
-                    .pop_float_vec()
-                    .map(Pushf64v)
-                    .and_then(|ins| machine.state.push_ins(ins).ok())
-                    .is_some()
+        }
+        PlainOp(Depthins) => {
+            let depth = machine.state.ins_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupins) => machine.state.dup_ins()?,
+        PlainOp(Swapins) => machine.state.swap_ins()?,
+        PlainOp(Flushins) => machine.state.flush_ins(),
+        PlainOp(Shoveins) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_ins(index(pos))?;
+        }
+        PlainOp(Depthi64) => {
+            let depth = machine.state.int_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupi64) => machine.state.dup_int()?,
+        PlainOp(Swapi64) => machine.state.swap_int()?,
+        PlainOp(Flushi64) => machine.state.flush_int(),
+        PlainOp(Shovei64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_int(index(pos))?;
+        }
+        PlainOp(Depthf64) => {
+            let depth = machine.state.float_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupf64) => machine.state.dup_float()?,
+        PlainOp(Swapf64) => machine.state.swap_float()?,
+        PlainOp(Flushf64) => machine.state.flush_float(),
+        PlainOp(Shovef64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_float(index(pos))?;
+        }
+        PlainOp(Depthb) => {
+            let depth = machine.state.bool_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupb) => machine.state.dup_bool()?,
+        PlainOp(Swapb) => machine.state.swap_bool()?,
+        PlainOp(Flushb) => machine.state.flush_bool(),
+        PlainOp(Shoveb) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_bool(index(pos))?;
+        }
+        PlainOp(Depths) => {
+            let depth = machine.state.string_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dups) => machine.state.dup_string()?,
+        PlainOp(Swaps) => machine.state.swap_string()?,
+        PlainOp(Flushs) => machine.state.flush_string(),
+        PlainOp(Shoves) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_string(index(pos))?;
+        }
+        PlainOp(Depthc) => {
+            let depth = machine.state.char_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupc) => machine.state.dup_char()?,
+        PlainOp(Swapc) => machine.state.swap_char()?,
+        PlainOp(Flushc) => machine.state.flush_char(),
+        PlainOp(Shovec) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_char(index(pos))?;
+        }
+        PlainOp(Depthinsv) => {
+            let depth = machine.state.ins_vec_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupinsv) => machine.state.dup_ins_vec()?,
+        PlainOp(Swapinsv) => machine.state.swap_ins_vec()?,
+        PlainOp(Flushinsv) => machine.state.flush_ins_vec(),
+        PlainOp(Shoveinsv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_ins_vec(index(pos))?;
+        }
+        PlainOp(Depthi64v) => {
+            let depth = machine.state.int_vec_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupi64v) => machine.state.dup_int_vec()?,
+        PlainOp(Swapi64v) => machine.state.swap_int_vec()?,
+        PlainOp(Flushi64v) => machine.state.flush_int_vec(),
+        PlainOp(Shovei64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_int_vec(index(pos))?;
+        }
+        PlainOp(Depthf64v) => {
+            let depth = machine.state.float_vec_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupf64v) => machine.state.dup_float_vec()?,
+        PlainOp(Swapf64v) => machine.state.swap_float_vec()?,
+        PlainOp(Flushf64v) => machine.state.flush_float_vec(),
+        PlainOp(Shovef64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_float_vec(index(pos))?;
+        }
+        PlainOp(Depthbv) => {
+            let depth = machine.state.bool_vec_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupbv) => machine.state.dup_bool_vec()?,
+        PlainOp(Swapbv) => machine.state.swap_bool_vec()?,
+        PlainOp(Flushbv) => machine.state.flush_bool_vec(),
+        PlainOp(Shovebv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_bool_vec(index(pos))?;
+        }
+        PlainOp(Depthexe) => {
+            let depth = machine.state.exe_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Shoveexe) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_exe(index(pos))?;
+        }
+        PlainOp(Yankexe) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_exe(index(pos))?;
+        }
+        PlainOp(YankDupexe) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_exe(index(pos))?;
+        }
+        PlainOp(Yankins) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_ins(index(pos))?;
+        }
+        PlainOp(YankDupins) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_ins(index(pos))?;
+        }
+        PlainOp(Yanki64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_int(index(pos))?;
+        }
+        PlainOp(YankDupi64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_int(index(pos))?;
+        }
+        PlainOp(Yankf64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_float(index(pos))?;
+        }
+        PlainOp(YankDupf64) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_float(index(pos))?;
+        }
+        PlainOp(Yankb) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_bool(index(pos))?;
+        }
+        PlainOp(YankDupb) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_bool(index(pos))?;
+        }
+        PlainOp(Yanks) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_string(index(pos))?;
+        }
+        PlainOp(YankDups) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_string(index(pos))?;
+        }
+        PlainOp(Yankc) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_char(index(pos))?;
+        }
+        PlainOp(YankDupc) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_char(index(pos))?;
+        }
+        PlainOp(Yankinsv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_ins_vec(index(pos))?;
+        }
+        PlainOp(YankDupinsv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_ins_vec(index(pos))?;
+        }
+        PlainOp(Yanki64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_int_vec(index(pos))?;
+        }
+        PlainOp(YankDupi64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_int_vec(index(pos))?;
+        }
+        PlainOp(Yankf64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_float_vec(index(pos))?;
+        }
+        PlainOp(YankDupf64v) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_float_vec(index(pos))?;
+        }
+        PlainOp(Yankbv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_bool_vec(index(pos))?;
+        }
+        PlainOp(YankDupbv) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_bool_vec(index(pos))?;
+        }
+        PlainOp(Yankn) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_name(index(pos))?;
+        }
+        PlainOp(YankDupn) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.yank_dup_name(index(pos))?;
+        }
+        PlainOp(Flushbindings) => machine.state.flush_bindings(),
+        PlainOp(CreateTag) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            machine.state.push_ins(Tag(n as u32))?;
+        }
+        PlainOp(CreateTagRef) => {
+            let n = index(machine.pop_int_or_handle(handled));
+            machine.state.push_ins(TagRef(n as u32))?;
+        }
+        PlainOp(Flushtags) => machine.state.flush_tags(),
+        PlainOp(Quotename) => match machine.state.pop_exe() {
+            Some(Name(n)) => machine.state.push_name(n)?,
+            Some(ins) => {
+                machine.state.push_exe(ins)?;
+                return Err(Fault::TypeMismatch);
+            }
+            None => return Err(Fault::Empty),
+        },
+        PlainOp(CreateName) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            machine.state.push_ins(Name(n))?;
+        }
+        PlainOp(Iton) => {
+            let a = machine.state.pop_int().ok_or(Fault::Empty)?;
+            machine.state.push_name(a as u32)?;
+        }
+        PlainOp(Defineins) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_ins().ok_or(Fault::Empty)?;
+            machine.state.bind(n, a)?;
+        }
+        PlainOp(Definei64) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_int().ok_or(Fault::Empty)?;
+            machine.state.bind(n, Pushi64(a))?;
+        }
+        PlainOp(Definef64) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_float().ok_or(Fault::Empty)?;
+            machine.state.bind(n, Pushf64(a))?;
+        }
+        PlainOp(Defineb) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_bool().ok_or(Fault::Empty)?;
+            machine.state.bind(n, Pushb(a))?;
+        }
+        PlainOp(Defines) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_string().ok_or(Fault::Empty)?;
+            machine.state.bind(n, Pushs(a))?;
+        }
+        PlainOp(Definec) => {
+            let n = machine.state.pop_name().ok_or(Fault::Empty)?;
+            let a = machine.state.pop_char().ok_or(Fault::Empty)?;
+            machine.state.bind(n, Pushc(a))?;
+        }
+        PlainOp(Rotn) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.rot_name(index(pos))?;
+        }
+        PlainOp(Copyn) => {
+            let pos = machine.pop_int_or_handle(handled);
+            let copy = machine.state.copy_name(index(pos)).ok_or(Fault::OutOfRange)?;
+            machine.state.push_name(copy)?;
+        }
+        PlainOp(Popn) => {
+            machine.state.pop_name().ok_or(Fault::Empty)?;
+        }
+        PlainOp(Depthn) => {
+            let depth = machine.state.name_len();
+            machine.state.push_int(depth as i64)?;
+        }
+        PlainOp(Dupn) => machine.state.dup_name()?,
+        PlainOp(Swapn) => machine.state.swap_name()?,
+        PlainOp(Flushn) => machine.state.flush_name(),
+        PlainOp(Shoven) => {
+            let pos = machine.pop_int_or_handle(handled);
+            machine.state.shove_name(index(pos))?;
+        }
+        PlainOp(In0i64) => {
+            let e = input(&machine.inputs.ints, 0)?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(In1i64) => {
+            let e = input(&machine.inputs.ints, 1)?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(In2i64) => {
+            let e = input(&machine.inputs.ints, 2)?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(In3i64) => {
+            let e = input(&machine.inputs.ints, 3)?;
+            machine.state.push_int(e)?;
+        }
+        PlainOp(In0f64) => {
+            let e = input(&machine.inputs.floats, 0)?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(In1f64) => {
+            let e = input(&machine.inputs.floats, 1)?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(In2f64) => {
+            let e = input(&machine.inputs.floats, 2)?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(In3f64) => {
+            let e = input(&machine.inputs.floats, 3)?;
+            machine.state.push_float(e)?;
+        }
+        PlainOp(In0b) => {
+            let e = input(&machine.inputs.bools, 0)?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(In1b) => {
+            let e = input(&machine.inputs.bools, 1)?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(In2b) => {
+            let e = input(&machine.inputs.bools, 2)?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(In3b) => {
+            let e = input(&machine.inputs.bools, 3)?;
+            machine.state.push_bool(e)?;
+        }
+        PlainOp(In0i64v) => {
+            let e = input(&machine.inputs.int_vecs, 0)?;
+            machine.state.push_int_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In1i64v) => {
+            let e = input(&machine.inputs.int_vecs, 1)?;
+            machine.state.push_int_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In2i64v) => {
+            let e = input(&machine.inputs.int_vecs, 2)?;
+            machine.state.push_int_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In3i64v) => {
+            let e = input(&machine.inputs.int_vecs, 3)?;
+            machine.state.push_int_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In0f64v) => {
+            let e = input(&machine.inputs.float_vecs, 0)?;
+            machine.state.push_float_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In1f64v) => {
+            let e = input(&machine.inputs.float_vecs, 1)?;
+            machine.state.push_float_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In2f64v) => {
+            let e = input(&machine.inputs.float_vecs, 2)?;
+            machine.state.push_float_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In3f64v) => {
+            let e = input(&machine.inputs.float_vecs, 3)?;
+            machine.state.push_float_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In0bv) => {
+            let e = input(&machine.inputs.bool_vecs, 0)?;
+            machine.state.push_bool_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In1bv) => {
+            let e = input(&machine.inputs.bool_vecs, 1)?;
+            machine.state.push_bool_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In2bv) => {
+            let e = input(&machine.inputs.bool_vecs, 2)?;
+            machine.state.push_bool_vec(TrackedVec::new_from_vec(e))?;
+        }
+        PlainOp(In3bv) => {
+            let e = input(&machine.inputs.bool_vecs, 3)?;
+            machine.state.push_bool_vec(TrackedVec::new_from_vec(e))?;
+        }
+        BasicBlock(mut b) => {
+            if let Some(i) = b.next() {
+                // The rest of the block is only needed if anything is left in it.
+                if !b.as_slice().is_empty() {
+                    machine.state.push_exe(BasicBlock(b))?;
+                }
+                machine.state.push_exe(i)?;
             }
-            PlainOp(Return) => machine.state.pop_exe().is_some(),
-            PlainOp(Yield) => {
-                machine
-                    .state
-                    .pop_exe()
-                    .and_then(|e| machine.state.push_ins(e).ok())
-                    .is_some()
+        }
+        Loop(mut l) => {
+            if let Some(i) = l.next() {
+                machine.state.push_exe(Loop(l))?;
+                machine.state.push_exe(i)?;
             }
-            PlainOp(Call) => {
-                machine
-                    .state
-                    .pop_ins()
-                    .and_then(|e| machine.state.push_exe(e).ok())
-                    .is_some()
+        }
+        If(b0, b1) => {
+            let decider = pop_bool_or_false(&mut machine.state, handled);
+            machine.state.push_exe(BasicBlock(if decider { b0 } else { b1 }))?;
+        }
+        DoTimes(body, push_index, None) => {
+            let n = machine.pop_int_or_handle(handled);
+            if n > 0 {
+                machine.state.push_exe(DoTimes(body, push_index, Some((0, n))))?;
             }
-            PlainOp(Nop) => true,
-            PlainOp(Provide) => {
-                ret_ins = machine.state.pop_ins();
-                ret_ins.is_some()
+        }
+        DoTimes(body, push_index, Some((ix, n))) => {
+            if ix + 1 < n {
+                machine.state.push_exe(DoTimes(body.clone(), push_index, Some((ix + 1, n))))?;
             }
-            BasicBlock(mut b) => {
-                if let Some(i) = b.next() {
-                    machine.state.push_exe(BasicBlock(b)).is_err() ||
-                    machine.state.push_exe(i).is_err()
-                } else {
-                    false
-                }
+            machine.state.push_exe(BasicBlock(body.into_iter()))?;
+            if push_index {
+                machine.state.push_int(ix)?;
             }
-            Loop(mut l) => {
-                if let Some(i) = l.next() {
-                    machine.state.push_exe(Loop(l)).is_err() || machine.state.push_exe(i).is_err()
-                } else {
-                    false
-                }
+        }
+        DoRange(body, None) => {
+            let end = machine.pop_int_or_handle(handled);
+            let start = machine.pop_int_or_handle(handled);
+            machine.state.push_exe(DoRange(body, Some((start, end))))?;
+        }
+        DoRange(body, Some((ix, end))) => {
+            if ix != end {
+                let next = if ix < end { ix + 1 } else { ix - 1 };
+                machine.state.push_exe(DoRange(body.clone(), Some((next, end))))?;
             }
-            If(b0, b1) => {
-                let decider = machine.state.pop_bool().unwrap_or(false);
-                machine
-                    .state
-                    .push_exe(BasicBlock(if decider { b0 } else { b1 }))
-                    .is_ok()
+            machine.state.push_exe(BasicBlock(body.into_iter()))?;
+            machine.state.push_int(ix)?;
+        }
+        While(body) => {
+            if pop_bool_or_false(&mut machine.state, handled) {
+                machine.state.push_exe(While(body.clone()))?;
+                machine.state.push_exe(BasicBlock(body.into_iter()))?;
             }
-            Pushi64(n) => machine.state.push_int(n).is_ok(),
-            Pushf64(n) => machine.state.push_float(n).is_ok(),
-            Pushb(b) => machine.state.push_bool(b).is_ok(),
-            Pushi64v(v) => machine.state.push_int_vec(v).is_ok(),
-            Pushf64v(v) => machine.state.push_float_vec(v).is_ok(),
-        };
-        (ret_ins, success)
+        }
+        Pushi64(n) => machine.state.push_int(n)?,
+        Pushf64(n) => machine.state.push_float(n)?,
+        Pushb(b) => machine.state.push_bool(b)?,
+        Pushi64v(v) => machine.state.push_int_vec(v)?,
+        Pushf64v(v) => machine.state.push_float_vec(v)?,
+        Pushs(s) => machine.state.push_string(s)?,
+        Pushc(c) => machine.state.push_char(c)?,
+        Pushbv(v) => machine.state.push_bool_vec(v)?,
+        Name(n) => match machine.state.binding(n).cloned() {
+            Some(ins) => machine.state.push_exe(ins)?,
+            None => machine.state.push_name(n)?,
+        },
+        Tag(n) => {
+            let ins = machine.state.pop_exe().ok_or(Fault::Empty)?;
+            machine.state.tag(n, ins)?;
+        }
+        TagRef(n) => {
+            let ins = machine.state.tagged(n).cloned().ok_or(Fault::Empty)?;
+            machine.state.push_exe(ins)?;
+        }
     }
+    Ok(None)
 }
-
@@ -1,10 +1,19 @@
 use vec::*;
 use TotalMemory;
 use HeapSizeOf;
+use StackDepths;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::collections::BTreeMap;
+
+/// The reason an operation on a `State` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum SizeError {
+    /// There wasn't enough memory left.
     Full,
+    /// There was nothing on the stack to operate on.
+    Empty,
+    /// A position or index was past the end of the stack or vector.
+    OutOfRange,
 }
 
 #[derive(Debug, Clone)]
@@ -13,7 +22,7 @@ pub struct State<Ins> {
     pub max_size: usize,
     /// The current memory used.
     size: usize,
-    /// Instructions are popped from this stack to be executed and this stack is not directly accessed.
+    /// Instructions are popped from this stack to be executed, and the exe stack operations can rearrange it.
     exe_stack: Vec<Ins>,
     /// This is a stack for handling instructions as data.
     ins_stack: Vec<Ins>,
@@ -23,12 +32,24 @@ pub struct State<Ins> {
     float_stack: Vec<f64>,
     /// This is a stack for bools.
     bool_stack: Vec<bool>,
+    /// This is a stack for strings.
+    string_stack: Vec<String>,
+    /// This is a stack for characters.
+    char_stack: Vec<char>,
+    /// This is a stack for names, which refer to bindings.
+    name_stack: Vec<u32>,
     /// This is a stack for instruction vectors.
     ins_vec_stack: Vec<TrackedVec<Ins>>,
     /// This is a stack for integer vectors.
     int_vec_stack: Vec<TrackedVec<i64>>,
     /// This is a stack for float vectors.
     float_vec_stack: Vec<TrackedVec<f64>>,
+    /// This is a stack for bool vectors.
+    bool_vec_stack: Vec<TrackedVec<bool>>,
+    /// The instruction each name is bound to.
+    bindings: BTreeMap<u32, Ins>,
+    /// The instruction stored under each tag.
+    tags: BTreeMap<u32, Ins>,
 }
 
 impl<Ins> HeapSizeOf for State<Ins> where Ins: HeapSizeOf {
@@ -47,9 +68,155 @@ impl<Ins> State<Ins> {
             int_stack: Vec::new(),
             float_stack: Vec::new(),
             bool_stack: Vec::new(),
+            string_stack: Vec::new(),
+            char_stack: Vec::new(),
+            name_stack: Vec::new(),
             ins_vec_stack: Vec::new(),
             int_vec_stack: Vec::new(),
             float_vec_stack: Vec::new(),
+            bool_vec_stack: Vec::new(),
+            bindings: BTreeMap::new(),
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// The memory currently used by everything in the state.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The exe stack, with the next instruction to execute at the end.
+    pub fn exe_stack(&self) -> &[Ins] {
+        &self.exe_stack
+    }
+
+    pub fn ins_stack(&self) -> &[Ins] {
+        &self.ins_stack
+    }
+
+    pub fn int_stack(&self) -> &[i64] {
+        &self.int_stack
+    }
+
+    pub fn float_stack(&self) -> &[f64] {
+        &self.float_stack
+    }
+
+    pub fn bool_stack(&self) -> &[bool] {
+        &self.bool_stack
+    }
+
+    pub fn string_stack(&self) -> &[String] {
+        &self.string_stack
+    }
+
+    pub fn char_stack(&self) -> &[char] {
+        &self.char_stack
+    }
+
+    pub fn name_stack(&self) -> &[u32] {
+        &self.name_stack
+    }
+
+    /// The instructions which names are bound to.
+    pub fn bindings(&self) -> &BTreeMap<u32, Ins> {
+        &self.bindings
+    }
+
+    /// The instruction `name` is bound to, if any.
+    pub fn binding(&self, name: u32) -> Option<&Ins> {
+        self.bindings.get(&name)
+    }
+
+    /// The instructions stored under each tag.
+    pub fn tags(&self) -> &BTreeMap<u32, Ins> {
+        &self.tags
+    }
+
+    /// The instruction stored under the tag closest to `tag`, which is the lowest tag no less than `tag`, wrapping
+    /// around to the lowest tag overall if there is none.
+    pub fn tagged(&self, tag: u32) -> Option<&Ins> {
+        self.tags.range(tag..).next().or_else(|| self.tags.iter().next()).map(|(_, ins)| ins)
+    }
+
+    pub fn ins_vec_stack(&self) -> &[TrackedVec<Ins>] {
+        &self.ins_vec_stack
+    }
+
+    pub fn int_vec_stack(&self) -> &[TrackedVec<i64>] {
+        &self.int_vec_stack
+    }
+
+    pub fn float_vec_stack(&self) -> &[TrackedVec<f64>] {
+        &self.float_vec_stack
+    }
+
+    pub fn bool_vec_stack(&self) -> &[TrackedVec<bool>] {
+        &self.bool_vec_stack
+    }
+
+    pub fn exe_len(&self) -> usize {
+        self.exe_stack.len()
+    }
+
+    pub fn ins_len(&self) -> usize {
+        self.ins_stack.len()
+    }
+
+    pub fn int_len(&self) -> usize {
+        self.int_stack.len()
+    }
+
+    pub fn float_len(&self) -> usize {
+        self.float_stack.len()
+    }
+
+    pub fn bool_len(&self) -> usize {
+        self.bool_stack.len()
+    }
+
+    pub fn string_len(&self) -> usize {
+        self.string_stack.len()
+    }
+
+    pub fn char_len(&self) -> usize {
+        self.char_stack.len()
+    }
+
+    pub fn name_len(&self) -> usize {
+        self.name_stack.len()
+    }
+
+    pub fn ins_vec_len(&self) -> usize {
+        self.ins_vec_stack.len()
+    }
+
+    pub fn int_vec_len(&self) -> usize {
+        self.int_vec_stack.len()
+    }
+
+    pub fn float_vec_len(&self) -> usize {
+        self.float_vec_stack.len()
+    }
+
+    pub fn bool_vec_len(&self) -> usize {
+        self.bool_vec_stack.len()
+    }
+
+    pub fn depths(&self) -> StackDepths {
+        StackDepths {
+            exe: self.exe_stack.len(),
+            ins: self.ins_stack.len(),
+            int: self.int_stack.len(),
+            float: self.float_stack.len(),
+            bool: self.bool_stack.len(),
+            string: self.string_stack.len(),
+            char: self.char_stack.len(),
+            name: self.name_stack.len(),
+            ins_vec: self.ins_vec_stack.len(),
+            int_vec: self.int_vec_stack.len(),
+            float_vec: self.float_vec_stack.len(),
+            bool_vec: self.bool_vec_stack.len(),
         }
     }
 }
@@ -112,6 +279,49 @@ impl<Ins> State<Ins>
         }
     }
 
+    pub fn push_string(&mut self, string: String) -> Result<(), SizeError> {
+        let size = string.total_memory();
+        if size + self.size > self.max_size {
+            Err(SizeError::Full)
+        } else {
+            self.size += size;
+            self.string_stack.push(string);
+            Ok(())
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) -> Result<(), SizeError> {
+        let size = c.total_memory();
+        if size + self.size > self.max_size {
+            Err(SizeError::Full)
+        } else {
+            self.size += size;
+            self.char_stack.push(c);
+            Ok(())
+        }
+    }
+
+    pub fn push_name(&mut self, name: u32) -> Result<(), SizeError> {
+        let size = name.total_memory();
+        if size + self.size > self.max_size {
+            Err(SizeError::Full)
+        } else {
+            self.size += size;
+            self.name_stack.push(name);
+            Ok(())
+        }
+    }
+
+    /// Bind `name` to `ins`, replacing any instruction it was already bound to.
+    pub fn bind(&mut self, name: u32, ins: Ins) -> Result<(), SizeError> {
+        insert_entry(&mut self.bindings, &mut self.size, self.max_size, name, ins)
+    }
+
+    /// Store `ins` under `tag`, replacing any instruction already stored under exactly that tag.
+    pub fn tag(&mut self, tag: u32, ins: Ins) -> Result<(), SizeError> {
+        insert_entry(&mut self.tags, &mut self.size, self.max_size, tag, ins)
+    }
+
     pub fn push_ins_vec(&mut self, ins_vec: TrackedVec<Ins>) -> Result<(), SizeError> {
         let size = ins_vec.total_memory();
         if size + self.size > self.max_size {
@@ -145,6 +355,73 @@ impl<Ins> State<Ins>
         }
     }
 
+    pub fn push_bool_vec(&mut self, bool_vec: TrackedVec<bool>) -> Result<(), SizeError> {
+        let size = bool_vec.total_memory();
+        if size + self.size > self.max_size {
+            Err(SizeError::Full)
+        } else {
+            self.size += size;
+            self.bool_vec_stack.push(bool_vec);
+            Ok(())
+        }
+    }
+
+    /// Seed the int stack with `ints`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_ints(&mut self, ints: &[i64]) -> Result<(), SizeError> {
+        ints.iter().try_for_each(|&int| self.push_int(int))
+    }
+
+    /// Seed the float stack with `floats`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_floats(&mut self, floats: &[f64]) -> Result<(), SizeError> {
+        floats.iter().try_for_each(|&float| self.push_float(float))
+    }
+
+    /// Seed the bool stack with `bools`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_bools(&mut self, bools: &[bool]) -> Result<(), SizeError> {
+        bools.iter().try_for_each(|&b| self.push_bool(b))
+    }
+
+    /// Seed the string stack with `strings`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_strings(&mut self, strings: &[&str]) -> Result<(), SizeError> {
+        strings.iter().try_for_each(|&string| self.push_string(string.to_string()))
+    }
+
+    /// Seed the char stack with `chars`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_chars(&mut self, chars: &[char]) -> Result<(), SizeError> {
+        chars.iter().try_for_each(|&c| self.push_char(c))
+    }
+
+    /// Seed the int vector stack with `int_vecs`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_int_vecs(&mut self, int_vecs: &[&[i64]]) -> Result<(), SizeError> {
+        int_vecs.iter().try_for_each(|&v| self.push_int_vec(TrackedVec::new_from_vec(v.to_vec())))
+    }
+
+    /// Seed the float vector stack with `float_vecs`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_float_vecs(&mut self, float_vecs: &[&[f64]]) -> Result<(), SizeError> {
+        float_vecs.iter().try_for_each(|&v| self.push_float_vec(TrackedVec::new_from_vec(v.to_vec())))
+    }
+
+    /// Seed the bool vector stack with `bool_vecs`, leaving the last one on top.
+    ///
+    /// If they don't all fit, those which did fit are left on the stack.
+    pub fn push_bool_vecs(&mut self, bool_vecs: &[&[bool]]) -> Result<(), SizeError> {
+        bool_vecs.iter().try_for_each(|&v| self.push_bool_vec(TrackedVec::new_from_vec(v.to_vec())))
+    }
+
     pub fn pop_exe(&mut self) -> Option<Ins> {
         if let Some(e) = self.exe_stack.pop() {
             self.size -= e.total_memory();
@@ -190,6 +467,33 @@ impl<Ins> State<Ins>
         }
     }
 
+    pub fn pop_string(&mut self) -> Option<String> {
+        if let Some(e) = self.string_stack.pop() {
+            self.size -= e.total_memory();
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    pub fn pop_char(&mut self) -> Option<char> {
+        if let Some(e) = self.char_stack.pop() {
+            self.size -= e.total_memory();
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    pub fn pop_name(&mut self) -> Option<u32> {
+        if let Some(e) = self.name_stack.pop() {
+            self.size -= e.total_memory();
+            Some(e)
+        } else {
+            None
+        }
+    }
+
     pub fn pop_ins_vec(&mut self) -> Option<TrackedVec<Ins>> {
         if let Some(e) = self.ins_vec_stack.pop() {
             self.size -= e.total_memory();
@@ -217,91 +521,144 @@ impl<Ins> State<Ins>
         }
     }
 
-    pub fn rot_exe(&mut self, pos: usize) -> bool {
+    pub fn pop_bool_vec(&mut self) -> Option<TrackedVec<bool>> {
+        if let Some(e) = self.bool_vec_stack.pop() {
+            self.size -= e.total_memory();
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    pub fn rot_exe(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.exe_stack.len();
         if pos < len {
             let e = self.exe_stack.remove(len - pos - 1);
             self.exe_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_ins(&mut self, pos: usize) -> bool {
+    pub fn rot_ins(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.ins_stack.len();
         if pos < len {
             let e = self.ins_stack.remove(len - pos - 1);
             self.ins_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_int(&mut self, pos: usize) -> bool {
+    pub fn rot_int(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.int_stack.len();
         if pos < len {
             let e = self.int_stack.remove(len - pos - 1);
             self.int_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_float(&mut self, pos: usize) -> bool {
+    pub fn rot_float(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.float_stack.len();
         if pos < len {
             let e = self.float_stack.remove(len - pos - 1);
             self.float_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_bool(&mut self, pos: usize) -> bool {
+    pub fn rot_bool(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.bool_stack.len();
         if pos < len {
             let e = self.bool_stack.remove(len - pos - 1);
             self.bool_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_ins_vec(&mut self, pos: usize) -> bool {
+    pub fn rot_string(&mut self, pos: usize) -> Result<(), SizeError> {
+        let len = self.string_stack.len();
+        if pos < len {
+            let e = self.string_stack.remove(len - pos - 1);
+            self.string_stack.push(e);
+            Ok(())
+        } else {
+            Err(SizeError::OutOfRange)
+        }
+    }
+
+    pub fn rot_char(&mut self, pos: usize) -> Result<(), SizeError> {
+        let len = self.char_stack.len();
+        if pos < len {
+            let e = self.char_stack.remove(len - pos - 1);
+            self.char_stack.push(e);
+            Ok(())
+        } else {
+            Err(SizeError::OutOfRange)
+        }
+    }
+
+    pub fn rot_name(&mut self, pos: usize) -> Result<(), SizeError> {
+        let len = self.name_stack.len();
+        if pos < len {
+            let e = self.name_stack.remove(len - pos - 1);
+            self.name_stack.push(e);
+            Ok(())
+        } else {
+            Err(SizeError::OutOfRange)
+        }
+    }
+
+    pub fn rot_ins_vec(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.ins_vec_stack.len();
         if pos < len {
             let e = self.ins_vec_stack.remove(len - pos - 1);
             self.ins_vec_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_int_vec(&mut self, pos: usize) -> bool {
+    pub fn rot_int_vec(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.int_vec_stack.len();
         if pos < len {
             let e = self.int_vec_stack.remove(len - pos - 1);
             self.int_vec_stack.push(e);
-            true
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
-    pub fn rot_float_vec(&mut self, pos: usize) -> bool {
+    pub fn rot_float_vec(&mut self, pos: usize) -> Result<(), SizeError> {
         let len = self.float_vec_stack.len();
         if pos < len {
             let e = self.float_vec_stack.remove(len - pos - 1);
             self.float_vec_stack.push(e);
-            true
+            Ok(())
+        } else {
+            Err(SizeError::OutOfRange)
+        }
+    }
+
+    pub fn rot_bool_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        let len = self.bool_vec_stack.len();
+        if pos < len {
+            let e = self.bool_vec_stack.remove(len - pos - 1);
+            self.bool_vec_stack.push(e);
+            Ok(())
         } else {
-            false
+            Err(SizeError::OutOfRange)
         }
     }
 
@@ -354,6 +711,33 @@ impl<Ins> State<Ins>
         }
     }
 
+    pub fn copy_string(&self, pos: usize) -> Option<String> {
+        let len = self.string_stack.len();
+        if pos < len {
+            unsafe {Some(self.string_stack.get_unchecked(len - pos - 1).clone())}
+        } else {
+            None
+        }
+    }
+
+    pub fn copy_char(&self, pos: usize) -> Option<char> {
+        let len = self.char_stack.len();
+        if pos < len {
+            unsafe {Some(*self.char_stack.get_unchecked(len - pos - 1))}
+        } else {
+            None
+        }
+    }
+
+    pub fn copy_name(&self, pos: usize) -> Option<u32> {
+        let len = self.name_stack.len();
+        if pos < len {
+            unsafe {Some(*self.name_stack.get_unchecked(len - pos - 1))}
+        } else {
+            None
+        }
+    }
+
     pub fn copy_ins_vec(&self, pos: usize) -> Option<TrackedVec<Ins>>
         where Ins: Clone
     {
@@ -383,48 +767,64 @@ impl<Ins> State<Ins>
         }
     }
 
-    pub fn push_ins_to_vec(&mut self, ins: Ins) -> Result<bool, SizeError> {
+    pub fn copy_bool_vec(&self, pos: usize) -> Option<TrackedVec<bool>> {
+        let len = self.bool_vec_stack.len();
+        if pos < len {
+            unsafe {Some(self.bool_vec_stack.get_unchecked(len - pos - 1).clone())}
+        } else {
+            None
+        }
+    }
+
+    pub fn push_ins_to_vec(&mut self, ins: Ins) -> Result<(), SizeError> {
         let size = ins.total_memory();
         if size + self.size > self.max_size {
             Err(SizeError::Full)
+        } else if let Some(v) = self.ins_vec_stack.last_mut() {
+            self.size += size;
+            v.push(ins);
+            Ok(())
         } else {
-            if let Some(ref mut v) = self.ins_vec_stack.last_mut() {
-                self.size += size;
-                v.push(ins);
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+            Err(SizeError::Empty)
         }
     }
 
-    pub fn push_int_to_vec(&mut self, int: i64) -> Result<bool, SizeError> {
+    pub fn push_int_to_vec(&mut self, int: i64) -> Result<(), SizeError> {
         let size = int.total_memory();
         if size + self.size > self.max_size {
             Err(SizeError::Full)
+        } else if let Some(v) = self.int_vec_stack.last_mut() {
+            self.size += size;
+            v.push(int);
+            Ok(())
         } else {
-            if let Some(ref mut v) = self.int_vec_stack.last_mut() {
-                self.size += size;
-                v.push(int);
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+            Err(SizeError::Empty)
         }
     }
 
-    pub fn push_float_to_vec(&mut self, float: f64) -> Result<bool, SizeError> {
+    pub fn push_float_to_vec(&mut self, float: f64) -> Result<(), SizeError> {
         let size = float.total_memory();
         if size + self.size > self.max_size {
             Err(SizeError::Full)
+        } else if let Some(v) = self.float_vec_stack.last_mut() {
+            self.size += size;
+            v.push(float);
+            Ok(())
+        } else {
+            Err(SizeError::Empty)
+        }
+    }
+
+    pub fn push_bool_to_vec(&mut self, b: bool) -> Result<(), SizeError> {
+        let size = b.total_memory();
+        if size + self.size > self.max_size {
+            Err(SizeError::Full)
+        } else if let Some(v) = self.bool_vec_stack.last_mut() {
+            self.size += size;
+            v.push(b);
+            Ok(())
         } else {
-            if let Some(ref mut v) = self.float_vec_stack.last_mut() {
-                self.size += size;
-                v.push(float);
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+            Err(SizeError::Empty)
         }
     }
 
@@ -455,18 +855,35 @@ impl<Ins> State<Ins>
         }
     }
 
-    pub fn get_ins_from_vec(&self, ix: usize) -> Option<Ins>
+    pub fn pop_bool_from_vec(&mut self) -> Option<bool> {
+        if let Some(b) = self.bool_vec_stack.last_mut().and_then(TrackedVec::pop) {
+            self.size -= b.total_memory();
+            Some(b)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_ins_from_vec(&self, ix: usize) -> Result<Ins, SizeError>
         where Ins: Clone
     {
-        self.ins_vec_stack.last().and_then(|v| v.get(ix)).cloned()
+        get_from_vec(&self.ins_vec_stack, ix)
+    }
+
+    pub fn get_int_from_vec(&self, ix: usize) -> Result<i64, SizeError> {
+        get_from_vec(&self.int_vec_stack, ix)
     }
 
-    pub fn get_int_from_vec(&self, ix: usize) -> Option<i64> {
-        self.int_vec_stack.last().and_then(|v| v.get(ix)).cloned()
+    pub fn get_float_from_vec(&self, ix: usize) -> Result<f64, SizeError> {
+        get_from_vec(&self.float_vec_stack, ix)
     }
 
-    pub fn get_float_from_vec(&self, ix: usize) -> Option<f64> {
-        self.float_vec_stack.last().and_then(|v| v.get(ix)).cloned()
+    pub fn get_bool_from_vec(&self, ix: usize) -> Result<bool, SizeError> {
+        get_from_vec(&self.bool_vec_stack, ix)
+    }
+
+    pub fn get_top_string(&self) -> Option<&str> {
+        self.string_stack.last().map(String::as_str)
     }
 
     pub fn get_top_ins_vec(&self) -> Option<&TrackedVec<Ins>> {
@@ -481,34 +898,469 @@ impl<Ins> State<Ins>
         self.float_vec_stack.last()
     }
 
-    pub fn write_ins_to_vec(&mut self, ix: usize, ins: Ins) -> Result<bool, SizeError> {
-        if let Some(e) = self.ins_vec_stack.last_mut().and_then(|v| v.get_mut(ix)) {
-            if self.size + ins.total_memory() < self.max_size + e.total_memory() {
-                self.size -= e.total_memory();
-                self.size += ins.total_memory();
-                *e = ins;
-                Ok(true)
-            } else {
-                Err(SizeError::Full)
-            }
-        } else {
-            Ok(false)
-        }
+    pub fn get_top_bool_vec(&self) -> Option<&TrackedVec<bool>> {
+        self.bool_vec_stack.last()
     }
 
-    pub fn write_int_to_vec(&mut self, ix: usize, int: i64) {
-        if let Some(e) = self.int_vec_stack.last_mut().and_then(|v| v.get_mut(ix)) {
-            self.size -= e.total_memory();
-            self.size += int.total_memory();
-            *e = int;
-        }
+    pub fn write_ins_to_vec(&mut self, ix: usize, ins: Ins) -> Result<(), SizeError> {
+        write_to_vec(&mut self.ins_vec_stack, &mut self.size, self.max_size, ix, ins)
     }
 
-    pub fn write_float_to_vec(&mut self, ix: usize, float: f64) {
-        if let Some(e) = self.float_vec_stack.last_mut().and_then(|v| v.get_mut(ix)) {
-            self.size -= e.total_memory();
-            self.size += float.total_memory();
-            *e = float;
-        }
+    pub fn write_int_to_vec(&mut self, ix: usize, int: i64) -> Result<(), SizeError> {
+        write_to_vec(&mut self.int_vec_stack, &mut self.size, self.max_size, ix, int)
+    }
+
+    pub fn write_float_to_vec(&mut self, ix: usize, float: f64) -> Result<(), SizeError> {
+        write_to_vec(&mut self.float_vec_stack, &mut self.size, self.max_size, ix, float)
+    }
+
+    pub fn write_bool_to_vec(&mut self, ix: usize, b: bool) -> Result<(), SizeError> {
+        write_to_vec(&mut self.bool_vec_stack, &mut self.size, self.max_size, ix, b)
+    }
+
+    pub fn dup_exe(&mut self) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        dup(&mut self.exe_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_ins(&mut self) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        dup(&mut self.ins_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_int(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.int_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_float(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.float_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_bool(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.bool_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_string(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.string_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_char(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.char_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_name(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.name_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_ins_vec(&mut self) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        dup(&mut self.ins_vec_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_int_vec(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.int_vec_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_float_vec(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.float_vec_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn dup_bool_vec(&mut self) -> Result<(), SizeError> {
+        dup(&mut self.bool_vec_stack, &mut self.size, self.max_size)
+    }
+
+    pub fn swap_exe(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.exe_stack)
+    }
+
+    pub fn swap_ins(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.ins_stack)
+    }
+
+    pub fn swap_int(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.int_stack)
+    }
+
+    pub fn swap_float(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.float_stack)
+    }
+
+    pub fn swap_bool(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.bool_stack)
+    }
+
+    pub fn swap_string(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.string_stack)
+    }
+
+    pub fn swap_char(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.char_stack)
+    }
+
+    pub fn swap_name(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.name_stack)
+    }
+
+    pub fn swap_ins_vec(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.ins_vec_stack)
+    }
+
+    pub fn swap_int_vec(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.int_vec_stack)
+    }
+
+    pub fn swap_float_vec(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.float_vec_stack)
+    }
+
+    pub fn swap_bool_vec(&mut self) -> Result<(), SizeError> {
+        swap(&mut self.bool_vec_stack)
+    }
+
+    pub fn shove_exe(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.exe_stack, pos)
+    }
+
+    pub fn shove_ins(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.ins_stack, pos)
+    }
+
+    pub fn shove_int(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.int_stack, pos)
+    }
+
+    pub fn shove_float(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.float_stack, pos)
+    }
+
+    pub fn shove_bool(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.bool_stack, pos)
+    }
+
+    pub fn shove_string(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.string_stack, pos)
+    }
+
+    pub fn shove_char(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.char_stack, pos)
+    }
+
+    pub fn shove_name(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.name_stack, pos)
+    }
+
+    pub fn shove_ins_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.ins_vec_stack, pos)
+    }
+
+    pub fn shove_int_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.int_vec_stack, pos)
+    }
+
+    pub fn shove_float_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.float_vec_stack, pos)
+    }
+
+    pub fn shove_bool_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        shove(&mut self.bool_vec_stack, pos)
+    }
+
+    /// Move the element `pos` below the top of the exe stack to the top, or the bottom element if `pos` is past it.
+    pub fn yank_exe(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.exe_stack, pos)
+    }
+
+    pub fn yank_ins(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.ins_stack, pos)
+    }
+
+    pub fn yank_int(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.int_stack, pos)
+    }
+
+    pub fn yank_float(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.float_stack, pos)
+    }
+
+    pub fn yank_bool(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.bool_stack, pos)
+    }
+
+    pub fn yank_string(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.string_stack, pos)
+    }
+
+    pub fn yank_char(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.char_stack, pos)
+    }
+
+    pub fn yank_ins_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.ins_vec_stack, pos)
+    }
+
+    pub fn yank_int_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.int_vec_stack, pos)
+    }
+
+    pub fn yank_float_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.float_vec_stack, pos)
+    }
+
+    pub fn yank_bool_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.bool_vec_stack, pos)
+    }
+
+    pub fn yank_name(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank(&mut self.name_stack, pos)
+    }
+
+    pub fn yank_dup_exe(&mut self, pos: usize) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        yank_dup(&mut self.exe_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_ins(&mut self, pos: usize) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        yank_dup(&mut self.ins_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_int(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.int_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_float(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.float_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_bool(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.bool_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_string(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.string_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_char(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.char_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_ins_vec(&mut self, pos: usize) -> Result<(), SizeError>
+        where Ins: Clone
+    {
+        yank_dup(&mut self.ins_vec_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_int_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.int_vec_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_float_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.float_vec_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_bool_vec(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.bool_vec_stack, &mut self.size, self.max_size, pos)
+    }
+
+    pub fn yank_dup_name(&mut self, pos: usize) -> Result<(), SizeError> {
+        yank_dup(&mut self.name_stack, &mut self.size, self.max_size, pos)
+    }
+
+    /// Remove everything from the exe stack, ending the program.
+    pub fn flush_exe(&mut self) {
+        flush(&mut self.exe_stack, &mut self.size)
+    }
+
+    pub fn flush_ins(&mut self) {
+        flush(&mut self.ins_stack, &mut self.size)
+    }
+
+    pub fn flush_int(&mut self) {
+        flush(&mut self.int_stack, &mut self.size)
+    }
+
+    pub fn flush_float(&mut self) {
+        flush(&mut self.float_stack, &mut self.size)
+    }
+
+    pub fn flush_bool(&mut self) {
+        flush(&mut self.bool_stack, &mut self.size)
+    }
+
+    pub fn flush_string(&mut self) {
+        flush(&mut self.string_stack, &mut self.size)
+    }
+
+    pub fn flush_char(&mut self) {
+        flush(&mut self.char_stack, &mut self.size)
+    }
+
+    pub fn flush_name(&mut self) {
+        flush(&mut self.name_stack, &mut self.size)
+    }
+
+    pub fn flush_ins_vec(&mut self) {
+        flush(&mut self.ins_vec_stack, &mut self.size)
+    }
+
+    pub fn flush_int_vec(&mut self) {
+        flush(&mut self.int_vec_stack, &mut self.size)
+    }
+
+    pub fn flush_float_vec(&mut self) {
+        flush(&mut self.float_vec_stack, &mut self.size)
+    }
+
+    pub fn flush_bool_vec(&mut self) {
+        flush(&mut self.bool_vec_stack, &mut self.size)
+    }
+
+    /// Unbind every name.
+    pub fn flush_bindings(&mut self) {
+        flush_entries(&mut self.bindings, &mut self.size)
+    }
+
+    /// Remove every tagged instruction.
+    pub fn flush_tags(&mut self) {
+        flush_entries(&mut self.tags, &mut self.size)
+    }
+}
+
+/// Clone element `ix` of the vector on top of `stack`.
+fn get_from_vec<T: Clone + TotalMemory>(stack: &[TrackedVec<T>], ix: usize) -> Result<T, SizeError> {
+    stack
+        .last()
+        .ok_or(SizeError::Empty)
+        .and_then(|v| v.as_slice().get(ix).cloned().ok_or(SizeError::OutOfRange))
+}
+
+/// Replace element `ix` of the vector on top of `stack`, accounting for the change in memory in `size`.
+fn write_to_vec<T>(stack: &mut [TrackedVec<T>],
+                   size: &mut usize,
+                   max_size: usize,
+                   ix: usize,
+                   e: T)
+                   -> Result<(), SizeError>
+    where T: TotalMemory
+{
+    let v = stack.last_mut().ok_or(SizeError::Empty)?;
+    let old = v.get(ix).ok_or(SizeError::OutOfRange)?.total_memory();
+    let new = e.total_memory();
+    if *size - old + new > max_size {
+        Err(SizeError::Full)
+    } else {
+        *size = *size - old + new;
+        v.replace(ix, e);
+        Ok(())
+    }
+}
+
+/// Insert `ins` into `map` under `key`, accounting for the change in memory in `size`.
+fn insert_entry<Ins>(map: &mut BTreeMap<u32, Ins>,
+                     size: &mut usize,
+                     max_size: usize,
+                     key: u32,
+                     ins: Ins)
+                     -> Result<(), SizeError>
+    where Ins: TotalMemory
+{
+    let old = map.get(&key).map_or(0, |old| key.total_memory() + old.total_memory());
+    let new = key.total_memory() + ins.total_memory();
+    if *size - old + new > max_size {
+        Err(SizeError::Full)
+    } else {
+        *size = *size - old + new;
+        map.insert(key, ins);
+        Ok(())
+    }
+}
+
+/// Remove every entry from `map`, releasing the memory of the keys and instructions from `size`.
+fn flush_entries<Ins: TotalMemory>(map: &mut BTreeMap<u32, Ins>, size: &mut usize) {
+    for (key, ins) in ::std::mem::take(map) {
+        *size -= key.total_memory() + ins.total_memory();
+    }
+}
+
+/// Push a copy of the top of `stack`, accounting for its memory in `size`.
+fn dup<T>(stack: &mut Vec<T>, size: &mut usize, max_size: usize) -> Result<(), SizeError>
+    where T: Clone + TotalMemory
+{
+    let copy = stack.last().ok_or(SizeError::Empty)?.clone();
+    let copy_size = copy.total_memory();
+    if copy_size + *size > max_size {
+        Err(SizeError::Full)
+    } else {
+        *size += copy_size;
+        stack.push(copy);
+        Ok(())
+    }
+}
+
+/// Exchange the top two elements of `stack`.
+fn swap<T>(stack: &mut [T]) -> Result<(), SizeError> {
+    let len = stack.len();
+    if len < 2 {
+        Err(SizeError::Empty)
+    } else {
+        stack.swap(len - 1, len - 2);
+        Ok(())
+    }
+}
+
+/// Move the top of `stack` down so that `pos` elements are above it, which undoes a rot by `pos`, or to the bottom if
+/// `pos` is past it.
+fn shove<T>(stack: &mut Vec<T>, pos: usize) -> Result<(), SizeError> {
+    let len = stack.len();
+    if len == 0 {
+        Err(SizeError::Empty)
+    } else {
+        let e = stack.pop().unwrap();
+        stack.insert(len - 1 - pos.min(len - 1), e);
+        Ok(())
+    }
+}
+
+/// Move the element `pos` below the top of `stack` to the top, or the bottom element if `pos` is past it.
+fn yank<T>(stack: &mut Vec<T>, pos: usize) -> Result<(), SizeError> {
+    let len = stack.len();
+    if len == 0 {
+        Err(SizeError::Empty)
+    } else {
+        let e = stack.remove(len - 1 - pos.min(len - 1));
+        stack.push(e);
+        Ok(())
+    }
+}
+
+/// Push a copy of the element `pos` below the top of `stack`, or of the bottom element if `pos` is past it,
+/// accounting for its memory in `size`.
+fn yank_dup<T>(stack: &mut Vec<T>, size: &mut usize, max_size: usize, pos: usize) -> Result<(), SizeError>
+    where T: Clone + TotalMemory
+{
+    let len = stack.len();
+    if len == 0 {
+        return Err(SizeError::Empty);
+    }
+    let copy = stack[len - 1 - pos.min(len - 1)].clone();
+    let copy_size = copy.total_memory();
+    if copy_size + *size > max_size {
+        Err(SizeError::Full)
+    } else {
+        *size += copy_size;
+        stack.push(copy);
+        Ok(())
+    }
+}
+
+/// Remove everything from `stack`, releasing its memory from `size`.
+fn flush<T: TotalMemory>(stack: &mut Vec<T>, size: &mut usize) {
+    for e in stack.drain(..) {
+        *size -= e.total_memory();
     }
 }
@@ -0,0 +1,86 @@
+use Fault;
+
+/// One of the stacks of a `State`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Stack {
+    Exe,
+    Ins,
+    Int,
+    Float,
+    Bool,
+    String,
+    Char,
+    Name,
+    InsVec,
+    IntVec,
+    FloatVec,
+    BoolVec,
+}
+
+/// The number of items on each stack of a `State`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StackDepths {
+    pub exe: usize,
+    pub ins: usize,
+    pub int: usize,
+    pub float: usize,
+    pub bool: usize,
+    pub string: usize,
+    pub char: usize,
+    pub name: usize,
+    pub ins_vec: usize,
+    pub int_vec: usize,
+    pub float_vec: usize,
+    pub bool_vec: usize,
+}
+
+impl StackDepths {
+    pub fn get(&self, stack: Stack) -> usize {
+        match stack {
+            Stack::Exe => self.exe,
+            Stack::Ins => self.ins,
+            Stack::Int => self.int,
+            Stack::Float => self.float,
+            Stack::Bool => self.bool,
+            Stack::String => self.string,
+            Stack::Char => self.char,
+            Stack::Name => self.name,
+            Stack::InsVec => self.ins_vec,
+            Stack::IntVec => self.int_vec,
+            Stack::FloatVec => self.float_vec,
+            Stack::BoolVec => self.bool_vec,
+        }
+    }
+}
+
+/// An observer which `Machine::cycle_traced` calls around every instruction it executes.
+///
+/// `memory` is the memory used by the `State` and `depths` are its stack depths at the time of the call. The
+/// instruction is only available to `before`, since executing it consumes it.
+pub trait Tracer<Ins> {
+    /// Called after the instruction is taken off the exe stack, but before it executes.
+    fn before(&mut self, _ins: &Ins, _memory: usize, _depths: &StackDepths) {}
+
+    /// Called after the instruction executes with its result and the instruction it provided, if any.
+    fn after(&mut self,
+             _result: Result<(), Fault>,
+             _provided: Option<&Ins>,
+             _memory: usize,
+             _depths: &StackDepths) {
+    }
+}
+
+/// A tracer which ignores everything.
+impl<Ins> Tracer<Ins> for () {}
+
+impl<Ins, T> Tracer<Ins> for &mut T
+    where T: Tracer<Ins>
+{
+    fn before(&mut self, ins: &Ins, memory: usize, depths: &StackDepths) {
+        (**self).before(ins, memory, depths)
+    }
+
+    fn after(&mut self, result: Result<(), Fault>, provided: Option<&Ins>, memory: usize, depths: &StackDepths) {
+        (**self).after(result, provided, memory, depths)
+    }
+}
@@ -1,7 +1,6 @@
 use TotalMemory;
 
 use std::vec;
-use std::iter;
 use heapsize::HeapSizeOf;
 
 #[derive(Debug, Clone)]
@@ -30,6 +29,16 @@ impl<T> TrackedVec<T>
         }
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
     #[inline]
     pub fn push(&mut self, e: T) {
         self.size += e.total_memory();
@@ -56,15 +65,25 @@ impl<T> TrackedVec<T>
     }
 
     #[inline]
-    pub fn get_mut(&mut self, ix: usize) -> Option<&mut T> {
-        self.vec.get_mut(ix)
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
     }
 
     #[inline]
-    pub fn into_iter(self) -> TrackedIter<T> {
-        TrackedIter {
-            iter: self.vec.into_iter(),
-            size: self.size,
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+
+    /// Replace the element at `ix`, returning the old one if `ix` was in range.
+    #[inline]
+    pub fn replace(&mut self, ix: usize, e: T) -> Option<T> {
+        if ix < self.vec.len() {
+            self.size += e.total_memory();
+            let old = ::std::mem::replace(&mut self.vec[ix], e);
+            self.size -= old.total_memory();
+            Some(old)
+        } else {
+            None
         }
     }
 
@@ -73,12 +92,42 @@ impl<T> TrackedVec<T>
         where T: Clone
     {
         TrackedCycleIter {
-            iter: self.vec.into_iter().cycle(),
+            vec: self.vec,
+            pos: 0,
             size: self.size,
         }
     }
 }
 
+impl<T> Default for TrackedVec<T>
+    where T: TotalMemory
+{
+    fn default() -> TrackedVec<T> {
+        TrackedVec::new()
+    }
+}
+
+impl<T> IntoIterator for TrackedVec<T> {
+    type Item = T;
+    type IntoIter = TrackedIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> TrackedIter<T> {
+        TrackedIter {
+            iter: self.vec.into_iter(),
+            size: self.size,
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for TrackedVec<T>
+    where T: TotalMemory
+{
+    fn from(v: Vec<T>) -> TrackedVec<T> {
+        TrackedVec::new_from_vec(v)
+    }
+}
+
 impl<T> HeapSizeOf for TrackedVec<T> {
     fn heap_size_of_children(&self) -> usize {
         self.size
@@ -91,6 +140,14 @@ pub struct TrackedIter<T> {
     size: usize,
 }
 
+impl<T> TrackedIter<T> {
+    /// The elements which have not been iterated over yet.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
 impl<T> Iterator for TrackedIter<T> {
     type Item = T;
     fn next(&mut self) -> Option<T> {
@@ -106,16 +163,30 @@ impl<T> HeapSizeOf for TrackedIter<T> {
 
 #[derive(Debug, Clone)]
 pub struct TrackedCycleIter<T> {
-    iter: iter::Cycle<vec::IntoIter<T>>,
+    vec: Vec<T>,
+    pos: usize,
     size: usize,
 }
 
+impl<T> TrackedCycleIter<T> {
+    /// The elements in the order they will next be produced, as the remainder of the current cycle followed by the
+    /// elements which were already produced in it.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (&self.vec[self.pos..], &self.vec[..self.pos])
+    }
+}
+
 impl<T> Iterator for TrackedCycleIter<T>
     where T: Clone
 {
     type Item = T;
     fn next(&mut self) -> Option<T> {
-        self.iter.next()
+        let e = self.vec.get(self.pos).cloned();
+        if e.is_some() {
+            self.pos = (self.pos + 1) % self.vec.len();
+        }
+        e
     }
 }
 
@@ -0,0 +1,182 @@
+extern crate gapush;
+
+use gapush::simple::{parse, ParseError, ParseErrorKind, PlainOp, SimpleInstruction};
+use gapush::simple::SimpleInstruction::{BasicBlock, DoRange, DoTimes, If, Loop, Name, Pushb, Pushbv, Pushc, Pushf64,
+                                        Pushf64v, Pushi64, Pushi64v, Pushs, Tag, TagRef, While};
+
+fn parse_one(src: &str) -> SimpleInstruction {
+    src.parse().unwrap_or_else(|e| panic!("`{}` failed to parse: {}", src, e))
+}
+
+fn parse_err(src: &str) -> ParseError {
+    match parse(src) {
+        Ok(p) => panic!("`{}` parsed as {:?}", src, p),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn literals() {
+    match parse_one("-7") {
+        Pushi64(-7) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("2.5") {
+        Pushf64(n) => assert_eq!(n, 2.5),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("1e-3") {
+        Pushf64(n) => assert_eq!(n, 1e-3),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("-inf") {
+        Pushf64(n) => assert_eq!(n, f64::NEG_INFINITY),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("NaN") {
+        Pushf64(n) => assert!(n.is_nan()),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("false") {
+        Pushb(false) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one(r#""a\tb\u{263A}""#) {
+        Pushs(ref s) if s == "a\tb\u{263A}" => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one(r"'\''") {
+        Pushc('\'') => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("name:3") {
+        Name(3) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("tag:4") {
+        Tag(4) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("ref:5") {
+        TagRef(5) => {}
+        ins => panic!("{:?}", ins),
+    }
+}
+
+#[test]
+fn vector_literals() {
+    match parse_one("i64[1 2 -3]") {
+        Pushi64v(ref v) => assert_eq!(v.as_slice(), &[1, 2, -3]),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("f64[]") {
+        Pushf64v(ref v) => assert!(v.is_empty()),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("b[true false]") {
+        Pushbv(ref v) => assert_eq!(v.as_slice(), &[true, false]),
+        ins => panic!("{:?}", ins),
+    }
+}
+
+#[test]
+fn blocks() {
+    let program = parse("( 3 4 Addi64 ( Inci64 ) CreateLoop ) ; a comment\nloop( Nop ) if( 1 )( 2 3 )").unwrap();
+    assert_eq!(program.len(), 3);
+    match program[0] {
+        BasicBlock(ref b) => {
+            let b = b.as_slice();
+            assert_eq!(b.len(), 5);
+            match (&b[2], &b[3], &b[4]) {
+                (SimpleInstruction::PlainOp(PlainOp::Addi64),
+                 BasicBlock(inner),
+                 SimpleInstruction::PlainOp(PlainOp::CreateLoop)) => assert_eq!(inner.as_slice().len(), 1),
+                _ => panic!("{:?}", b),
+            }
+        }
+        ref ins => panic!("{:?}", ins),
+    }
+    match program[1] {
+        Loop(ref l) => assert_eq!(l.as_slices().0.len(), 1),
+        ref ins => panic!("{:?}", ins),
+    }
+    match program[2] {
+        If(ref t, ref f) => assert_eq!((t.as_slice().len(), f.as_slice().len()), (1, 2)),
+        ref ins => panic!("{:?}", ins),
+    }
+}
+
+#[test]
+fn loops() {
+    match parse_one("while( Notb )") {
+        While(ref b) => assert_eq!(b.len(), 1),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("times( Nop Nop )") {
+        DoTimes(ref b, false, None) => assert_eq!(b.len(), 2),
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("itimes( Nop )") {
+        DoTimes(_, true, None) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("times@1:3( Nop )") {
+        DoTimes(_, false, Some((1, 3))) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("itimes@0:2( Nop )") {
+        DoTimes(_, true, Some((0, 2))) => {}
+        ins => panic!("{:?}", ins),
+    }
+    match parse_one("range@4:10( Nop )") {
+        DoRange(_, Some((4, 10))) => {}
+        ins => panic!("{:?}", ins),
+    }
+}
+
+#[test]
+fn plain_ops_by_name() {
+    for &op in PlainOp::ALL {
+        assert_eq!(op.name().parse::<PlainOp>(), Ok(op));
+    }
+    assert!("addi64".parse::<PlainOp>().is_err());
+}
+
+#[test]
+fn errors_report_line_and_column() {
+    let e = parse_err("Nop\n  Foo");
+    assert_eq!(e.kind, ParseErrorKind::UnknownInstruction("Foo".to_string()));
+    assert_eq!((e.line, e.column), (2, 3));
+    assert_eq!(e.to_string(), "2:3: unknown instruction `Foo`");
+
+    let e = parse_err("Nop ( 3");
+    assert_eq!(e.kind, ParseErrorKind::Unclosed('('));
+    assert_eq!((e.line, e.column), (1, 5));
+
+    let e = parse_err("1 )");
+    assert_eq!(e.kind, ParseErrorKind::UnexpectedToken(")".to_string()));
+    assert_eq!((e.line, e.column), (1, 3));
+
+    let e = parse_err("i64[1 x]");
+    assert_eq!(e.kind, ParseErrorKind::InvalidLiteral("x".to_string()));
+    assert_eq!((e.line, e.column), (1, 7));
+
+    let e = parse_err("times@3( Nop )");
+    assert_eq!(e.kind, ParseErrorKind::InvalidLiteral("times@3".to_string()));
+
+    let e = parse_err("loop 3");
+    assert_eq!(e.kind, ParseErrorKind::Expected("`(`"));
+    assert_eq!((e.line, e.column), (1, 6));
+
+    let e = parse_err("\"abc");
+    assert_eq!(e.kind, ParseErrorKind::Unclosed('"'));
+
+    let e = parse_err("99999999999999999999");
+    assert_eq!(e.kind, ParseErrorKind::InvalidLiteral("99999999999999999999".to_string()));
+
+    let e = parse_err("'ab'");
+    assert_eq!(e.kind, ParseErrorKind::InvalidLiteral("'ab'".to_string()));
+
+    assert!("".parse::<SimpleInstruction>().is_err());
+    assert!("1 2".parse::<SimpleInstruction>().is_err());
+}
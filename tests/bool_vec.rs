@@ -0,0 +1,56 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine};
+
+/// Run `src` to completion, returning the machine and the number of failed instructions.
+fn run(src: &str) -> (TestMachine, usize) {
+    let mut m = machine(src);
+    let failures = m.run(1000).failures;
+    (m, failures)
+}
+
+fn bool_vecs(m: &TestMachine) -> Vec<&[bool]> {
+    m.state.bool_vec_stack().iter().map(|v| v.as_slice()).collect()
+}
+
+#[test]
+fn push_and_pop_elements() {
+    let (m, failures) = run("( b[true] false Pushvb true Pushvb Popvb )");
+    assert_eq!(failures, 0);
+    assert_eq!(bool_vecs(&m), [&[true, false][..]]);
+    // Like Popvi64, the element is dropped.
+    assert!(m.state.bool_stack().is_empty());
+
+    let (m, failures) = run("( b[] Popvb )");
+    assert_eq!(failures, 1);
+    assert_eq!(bool_vecs(&m), [&[][..]]);
+}
+
+#[test]
+fn read_and_write_elements() {
+    let (m, failures) = run("( b[true false true] 1 Readvb true 1 Writevb 0 Readvb )");
+    assert_eq!(failures, 0);
+    assert_eq!(bool_vecs(&m), [&[true, true, true][..]]);
+    assert_eq!(m.state.bool_stack(), &[false, true]);
+
+    let (m, failures) = run("( b[true] 1 Readvb )");
+    assert_eq!(failures, 1);
+    assert!(m.state.bool_stack().is_empty());
+}
+
+#[test]
+fn stack_manipulation() {
+    let (m, failures) = run("( b[true] b[false] b[] 2 Rotbv 1 Copybv Popbv )");
+    assert_eq!(failures, 0);
+    assert_eq!(bool_vecs(&m), [&[false][..], &[], &[true]]);
+}
+
+#[test]
+fn literals_and_construction() {
+    let (m, failures) = run("( b[false true] CreatePushbv Call )");
+    assert_eq!(failures, 0);
+    assert_eq!(bool_vecs(&m), [&[false, true][..]]);
+    assert!(m.state.ins_stack().is_empty());
+}
@@ -0,0 +1,86 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with};
+use gapush::simple::*;
+
+/// Run `src` to completion, returning the machine and the number of handled instructions.
+fn run(src: &str) -> (TestMachine, usize) {
+    let mut m = machine_with(1 << 16, || -1, || -1.0);
+    m.provide(src.parse().unwrap());
+    let outcome = m.run(1000);
+    assert_eq!(outcome.failures, 0, "{}", src);
+    (m, outcome.handled)
+}
+
+fn int_vecs(m: &TestMachine) -> Vec<&[i64]> {
+    m.state.int_vec_stack().iter().map(|v| v.as_slice()).collect()
+}
+
+fn float_vecs(m: &TestMachine) -> Vec<&[f64]> {
+    m.state.float_vec_stack().iter().map(|v| v.as_slice()).collect()
+}
+
+#[test]
+fn reshaping_int_vecs() {
+    let (m, _) = run("( i64[3 1 2] Lenvi64 i64[3 1 2] Revvi64 i64[3 1 2] Sortvi64 i64[1] i64[2 3] Catvi64 )");
+    assert_eq!(m.state.int_stack(), &[3]);
+    assert_eq!(int_vecs(&m), [&[2, 1, 3][..], &[1, 2, 3], &[1, 2, 3]]);
+
+    let (m, _) = run("( i64[1 2 3 4] 1 3 Slicevi64 i64[1 2 3 4] 2 Takevi64 i64[1 2 3 4] 3 Dropvi64 )");
+    assert_eq!(int_vecs(&m), [&[2, 3][..], &[1, 2], &[4]]);
+
+    // Out of range bounds are clamped.
+    let (m, _) = run("( i64[1 2] 1 9 Slicevi64 i64[1 2] 2 1 Slicevi64 i64[1 2] 9 Takevi64 i64[1 2] 9 Dropvi64 )");
+    assert_eq!(int_vecs(&m), [&[2][..], &[], &[1, 2], &[]]);
+}
+
+#[test]
+fn reducing_int_vecs() {
+    let (m, handled) = run("( i64[1 2 3 4] Sumvi64 i64[1 2 3 4] Prodvi64 i64[5 -2 9] Minvi64 i64[5 -2 9] Maxvi64 \
+                           i64[1 2] i64[3 4 5] Dotvi64 )");
+    assert_eq!(handled, 0);
+    assert_eq!(m.state.int_stack(), &[10, 24, -2, 9, 11]);
+
+    let (m, _) = run("( i64[1 2] Meanvi64 )");
+    assert_eq!(m.state.float_stack(), &[1.5]);
+
+    // Reductions with no identity are undefined for an empty vec.
+    let (m, handled) = run("( i64[] Sumvi64 i64[] Minvi64 i64[] Maxvi64 i64[] Meanvi64 )");
+    assert_eq!(handled, 3);
+    assert_eq!(m.state.int_stack(), &[0, -1, -1]);
+    assert_eq!(m.state.float_stack(), &[-1.0]);
+}
+
+#[test]
+fn float_vec_ops() {
+    let (m, _) = run("( f64[2.0 -1.0 0.5] Sortvf64 f64[2.0 -1.0] Revvf64 f64[1.0] f64[2.0] Catvf64 \
+                      f64[1.0 2.0 3.0] 1 2 Slicevf64 f64[1.0 2.0] 1 Takevf64 f64[1.0 2.0] 1 Dropvf64 )");
+    assert_eq!(float_vecs(&m),
+               [&[-1.0, 0.5, 2.0][..], &[-1.0, 2.0], &[1.0, 2.0], &[2.0], &[1.0], &[2.0]]);
+
+    let (m, handled) = run("( f64[1.0 2.0 4.0] Lenvf64 f64[1.0 2.0 4.0] Sumvf64 f64[1.0 2.0 4.0] Prodvf64 \
+                            f64[1.0 2.0 4.0] Minvf64 f64[1.0 2.0 4.0] Maxvf64 f64[1.0 2.0] Meanvf64 \
+                            f64[1.0 2.0] f64[3.0 0.5 9.0] Dotvf64 f64[] Maxvf64 )");
+    assert_eq!(handled, 1);
+    assert_eq!(m.state.int_stack(), &[3]);
+    assert_eq!(m.state.float_stack(), &[7.0, 8.0, 1.0, 4.0, 1.5, 4.0, -1.0]);
+}
+
+#[test]
+fn results_are_charged_against_memory() {
+    let mut m = machine_with(1 << 16, || -1, || -1.0);
+    m.state.push_int_vecs(&[&[0; 60], &[0; 40]]).unwrap();
+    m.provide(SimpleInstruction::PlainOp(PlainOp::Catvi64));
+    assert!(m.cycle().1.is_ok());
+    let mut expected = machine_with(1 << 16, || -1, || -1.0);
+    expected.state.push_int_vecs(&[&[0; 100]]).unwrap();
+    assert_eq!(m.state.size(), expected.state.size());
+
+    m.provide("( 10 Takevi64 )".parse().unwrap());
+    m.run(10);
+    let mut expected = machine_with(1 << 16, || -1, || -1.0);
+    expected.state.push_int_vecs(&[&[0; 10]]).unwrap();
+    assert_eq!(m.state.size(), expected.state.size());
+}
@@ -0,0 +1,60 @@
+extern crate gapush;
+
+mod common;
+
+use common::machine;
+use gapush::Termination;
+
+/// Run `src` until it halts, returning the int stack.
+fn ints(src: &str) -> Vec<i64> {
+    let mut m = machine(src);
+    let outcome = m.run(1000);
+    match outcome.termination {
+        Termination::Halted => {}
+        t => panic!("`{}` didn't halt: {:?}", src, t),
+    }
+    assert_eq!(outcome.failures, 0, "{}", src);
+    m.state.int_stack().to_vec()
+}
+
+#[test]
+fn k_drops_the_second_instruction() {
+    assert_eq!(ints("Kexe 1 2 3"), [1, 3]);
+}
+
+#[test]
+fn s_runs_the_third_instruction_twice() {
+    assert_eq!(ints("Sexe 1 2 3 4"), [1, 3, 2, 3, 4]);
+}
+
+#[test]
+fn y_repeats_its_instruction() {
+    let mut m = machine("Yexe Inci64");
+    m.state.push_int(0).unwrap();
+    let outcome = m.run(100);
+    match outcome.termination {
+        Termination::StepLimit => {}
+        t => panic!("{:?}", t),
+    }
+    assert!(m.state.int_stack()[0] > 10);
+
+    // The repetition can be stopped by returning from it.
+    assert_eq!(ints("Yexe ( 1 Return )"), [1]);
+}
+
+#[test]
+fn combinators_need_enough_instructions() {
+    let mut m = machine("Kexe");
+    assert_eq!(m.run(10).failures, 1);
+    let mut m = machine("Sexe 1 2");
+    assert_eq!(m.run(10).failures, 1);
+    assert_eq!(m.state.int_stack(), &[1, 2]);
+}
+
+#[test]
+fn exe_stack_manipulation() {
+    assert_eq!(ints("Dupexe 1 2"), [1, 1, 2]);
+    assert_eq!(ints("Swapexe 1 2 3"), [2, 1, 3]);
+    assert_eq!(ints("2 Rotexe 1 2 3"), [3, 1, 2]);
+    assert!(ints("Flushexe 1 2").is_empty());
+}
@@ -0,0 +1,36 @@
+//! Machines shared by the integration tests.
+
+// Each test crate only uses some of these.
+#![allow(dead_code)]
+
+use gapush::Machine;
+use gapush::simple::{parse, PlainOp, SimpleInstruction};
+
+pub type TestMachine = Machine<SimpleInstruction, fn() -> SimpleInstruction, fn() -> i64, fn() -> f64>;
+
+/// An empty machine with `max_size` bytes of memory, whose handlers supply `Nop`, `int_handler` and `float_handler`.
+pub fn machine_with(max_size: usize, int_handler: fn() -> i64, float_handler: fn() -> f64) -> TestMachine {
+    Machine::new(max_size,
+                 (|| SimpleInstruction::PlainOp(PlainOp::Nop)) as fn() -> SimpleInstruction,
+                 int_handler,
+                 float_handler)
+}
+
+/// An empty machine with 64 KiB of memory, whose handlers supply `Nop`, 0 and 0.0.
+pub fn new_machine() -> TestMachine {
+    machine_with(1 << 16, || 0, || 0.0)
+}
+
+/// Provide the top level instructions of `src` so that the first is on top of the exe stack.
+pub fn provide(machine: &mut TestMachine, src: &str) {
+    for ins in parse(src).unwrap().into_iter().rev() {
+        machine.provide(ins);
+    }
+}
+
+/// A `new_machine` with `src` provided to it.
+pub fn machine(src: &str) -> TestMachine {
+    let mut machine = new_machine();
+    provide(&mut machine, src);
+    machine
+}
@@ -0,0 +1,196 @@
+extern crate gapush;
+extern crate rand;
+
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+use std::collections::BTreeSet;
+
+const TRIALS: u32 = 200;
+
+/// A parent whose genes are the ints `start..start + len`, so every gene of a child can be traced to its parent.
+fn parent(start: i64, len: i64, crossovers: &[usize]) -> Chromosome {
+    Chromosome::new((start..start + len).map(Gene::Int).collect(),
+                    crossovers.iter().cloned().collect::<BTreeSet<_>>())
+}
+
+fn ints(c: &Chromosome) -> Vec<i64> {
+    c.genes()
+        .iter()
+        .map(|g| match *g {
+            Gene::Int(n) => n,
+            ref g => panic!("unexpected gene {:?}", g),
+        })
+        .collect()
+}
+
+/// Cross `a` (ints from 0) with `b` (ints from 100) many times, checking every child with `check`.
+fn cross_all<C, F>(crossover: &C, a: &Chromosome, b: &Chromosome, mut check: F)
+    where C: Crossover,
+          F: FnMut(&[i64])
+{
+    for seed in 1..TRIALS + 1 {
+        let mut rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+        let child = crossover.cross(a, b, &mut rng);
+        check(&ints(&child));
+        assert!(child.crossovers().iter().all(|&n| n < child.gene_len()));
+    }
+}
+
+fn both_contribute(genes: &[i64]) {
+    assert!(genes.iter().any(|&n| n < 100), "nothing from a in {:?}", genes);
+    assert!(genes.iter().any(|&n| n >= 100), "nothing from b in {:?}", genes);
+}
+
+#[test]
+fn crossover_points_alternate_between_parents() {
+    let a = parent(0, 20, &[5, 15]);
+    let b = parent(100, 20, &[10]);
+    let child = a.mate(&b);
+    let expected = (0..5).chain(105..110).chain(10..15).chain(115..120).collect::<Vec<_>>();
+    assert_eq!(ints(&child), expected);
+    cross_all(&CrossoverPoints, &a, &b, both_contribute);
+}
+
+#[test]
+fn crossover_points_stay_inside_the_child() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 30, &[25]);
+    let child = a.mate(&b);
+    assert_eq!(ints(&child), (0..20).chain(120..125).collect::<Vec<_>>());
+    // The end of the child isn't somewhere to switch parents.
+    assert_eq!(child.crossovers().iter().cloned().collect::<Vec<_>>(), vec![20]);
+    assert_eq!(ints(&child.mate(&b)), (0..20).chain(120..125).collect::<Vec<_>>());
+}
+
+#[test]
+fn uniform_keeps_positions() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 20, &[]);
+    cross_all(&Uniform, &a, &b, |genes| {
+        both_contribute(genes);
+        assert_eq!(genes.len(), 20);
+        for (i, &n) in genes.iter().enumerate() {
+            assert_eq!(n % 100, i as i64);
+        }
+    });
+}
+
+#[test]
+fn one_point_joins_a_prefix_and_suffix() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 20, &[]);
+    cross_all(&OnePoint, &a, &b, |genes| {
+        both_contribute(genes);
+        let cut = genes.iter().position(|&n| n >= 100).unwrap();
+        assert_eq!(genes.to_vec(), (0..cut as i64).chain(100 + cut as i64..120).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn two_point_replaces_a_middle_segment() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 20, &[]);
+    cross_all(&TwoPoint, &a, &b, |genes| {
+        both_contribute(genes);
+        assert_eq!(genes.len(), 20);
+        assert!(genes[0] < 100 && genes[19] < 100);
+        let from_b = genes.iter().filter(|&&n| n >= 100).count();
+        let first = genes.iter().position(|&n| n >= 100).unwrap();
+        assert!(genes[first..first + from_b].iter().all(|&n| n >= 100));
+    });
+}
+
+#[test]
+fn alternation_switches_parents() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 20, &[]);
+    let alternation = Alternation {
+        rate: 0.2,
+        alignment_deviation: 0.0,
+    };
+    let mut mixed = 0;
+    cross_all(&alternation, &a, &b, |genes| {
+        // Without alignment deviation the walk never skips or repeats a position.
+        for (i, &n) in genes.iter().enumerate() {
+            assert_eq!(n % 100, i as i64);
+        }
+        if genes.iter().any(|&n| n < 100) && genes.iter().any(|&n| n >= 100) {
+            mixed += 1;
+        }
+    });
+    assert!(mixed > TRIALS * 9 / 10, "only {} of {} children were mixed", mixed, TRIALS);
+
+    let aligned = Alternation {
+        rate: 0.2,
+        alignment_deviation: 2.0,
+    };
+    cross_all(&aligned, &a, &b, |genes| assert!(genes.len() <= 40));
+}
+
+#[test]
+fn size_fair_keeps_the_size_close() {
+    let a = parent(0, 20, &[]);
+    let b = parent(100, 30, &[]);
+    cross_all(&SizeFair, &a, &b, |genes| {
+        both_contribute(genes);
+        let removed = 20 - genes.iter().filter(|&&n| n < 100).count();
+        let inserted = genes.iter().filter(|&&n| n >= 100).count();
+        assert!(removed >= 1 && inserted >= 1 && inserted <= 2 * removed);
+    });
+}
+
+#[test]
+fn empty_parents() {
+    let empty = parent(0, 0, &[]);
+    let b = parent(100, 5, &[2]);
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    OnePoint.cross(&empty, &b, &mut rng);
+    TwoPoint.cross(&b, &empty, &mut rng);
+    Uniform.cross(&empty, &b, &mut rng);
+    SizeFair.cross(&empty, &b, &mut rng);
+    CrossoverPoints.cross(&empty, &b, &mut rng);
+    Alternation {
+            rate: 0.5,
+            alignment_deviation: 1.0,
+        }
+        .cross(&b, &empty, &mut rng);
+}
+
+/// Cross `a` with `b` using `crossover`, checking that every gene of every child is a whole gene of a parent.
+fn keeps_whole_genes<C: Crossover>(crossover: &C, a: &Chromosome, b: &Chromosome) {
+    for seed in 1..TRIALS + 1 {
+        let mut rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+        let child = crossover.cross(a, b, &mut rng);
+        for gene in child.genes() {
+            assert!(a.genes().contains(gene) || b.genes().contains(gene), "{:?}", gene);
+        }
+    }
+}
+
+#[test]
+fn genes_of_every_kind_are_copied_whole() {
+    let a = Chromosome::new(vec![Gene::Open(Block::Loop),
+                                 Gene::Float(0.5),
+                                 Gene::IntVec(vec![1, 2]),
+                                 Gene::Close,
+                                 Gene::Tag(3)],
+                            [2].iter().cloned().collect());
+    let b = Chromosome::new(vec![Gene::Op(PlainOp::Addi64),
+                                 Gene::BoolVec(vec![true]),
+                                 Gene::TagRef(3),
+                                 Gene::FloatVec(vec![0.25]),
+                                 Gene::Bool(false),
+                                 Gene::Int(7)],
+                            [1, 4].iter().cloned().collect());
+    keeps_whole_genes(&CrossoverPoints, &a, &b);
+    keeps_whole_genes(&Uniform, &a, &b);
+    keeps_whole_genes(&OnePoint, &a, &b);
+    keeps_whole_genes(&TwoPoint, &a, &b);
+    keeps_whole_genes(&SizeFair, &a, &b);
+    keeps_whole_genes(&Alternation {
+                          rate: 0.5,
+                          alignment_deviation: 1.0,
+                      },
+                      &a,
+                      &b);
+}
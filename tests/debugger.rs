@@ -0,0 +1,97 @@
+extern crate gapush;
+
+mod common;
+
+use common::machine;
+use gapush::Stack;
+use gapush::simple::*;
+
+type TestDebugger = Debugger<fn() -> SimpleInstruction, fn() -> i64, fn() -> f64>;
+
+fn debugger(src: &str) -> TestDebugger {
+    Debugger::new(machine(src))
+}
+
+fn is_breakpoint(stop: &Stop, ix: usize) -> bool {
+    match *stop {
+        Stop::Breakpoint(n) => n == ix,
+        _ => false,
+    }
+}
+
+#[test]
+fn op_breakpoint_stops_before_the_next_instruction() {
+    let mut d = debugger("Addi64 1 Addi64");
+    let stop = d.run_until_op(PlainOp::Addi64, 10);
+    assert!(is_breakpoint(&stop, 0), "{:?}", stop);
+    assert_eq!(d.steps, 0);
+
+    // Resuming runs the operation it stopped at and stops at the next one.
+    let stop = d.run_until_op(PlainOp::Addi64, 10);
+    assert!(is_breakpoint(&stop, 0), "{:?}", stop);
+    assert_eq!(d.steps, 2);
+    assert_eq!(d.listing(), "Addi64\n");
+
+    match d.run_until_op(PlainOp::Addi64, 10) {
+        Stop::Halted => {}
+        stop => panic!("{:?}", stop),
+    }
+    assert_eq!(d.state().int_stack(), &[1]);
+}
+
+#[test]
+fn op_breakpoint_inside_blocks() {
+    let mut d = debugger("( 1 ( 2 Muli64 ) )");
+    d.breakpoints.push(Breakpoint::Op(PlainOp::Muli64));
+    assert!(is_breakpoint(&d.run(100), 0));
+    assert_eq!(d.state().int_stack(), &[1, 2]);
+    match d.next_instruction() {
+        Some(&SimpleInstruction::PlainOp(PlainOp::Muli64)) => {}
+        ins => panic!("{:?}", ins),
+    }
+    // Stepping past it by hand also clears the stop.
+    assert!(d.step().1.is_ok());
+    assert_eq!(d.state().int_stack(), &[2]);
+    match d.run(100) {
+        Stop::Halted => {}
+        stop => panic!("{:?}", stop),
+    }
+}
+
+#[test]
+fn depth_and_memory_breakpoints() {
+    let mut d = debugger("1 2 3 4");
+    d.breakpoints.push(Breakpoint::Depth(Stack::Int, 3));
+    assert!(is_breakpoint(&d.run(100), 0));
+    assert_eq!(d.state().int_len(), 3);
+    // The depth has to be crossed again to stop again.
+    match d.run(100) {
+        Stop::Halted => {}
+        stop => panic!("{:?}", stop),
+    }
+
+    // Moving the vector onto its stack doesn't use more memory, but copying it does.
+    let mut d = debugger("i64[1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16] Dupi64v Dupi64v");
+    let memory = d.state().size();
+    d.breakpoints.push(Breakpoint::Memory(memory + 1));
+    assert!(is_breakpoint(&d.run(100), 0));
+    assert_eq!(d.steps, 2);
+}
+
+#[test]
+fn other_stops() {
+    let mut d = debugger("1 2 3");
+    match d.run(2) {
+        Stop::StepLimit => {}
+        stop => panic!("{:?}", stop),
+    }
+    assert_eq!(d.steps, 2);
+
+    let mut d = debugger("Nop CreatePushi64 Provide 5");
+    d.machine.state.push_int(9).unwrap();
+    match d.run(100) {
+        Stop::Provided(SimpleInstruction::Pushi64(9)) => {}
+        stop => panic!("{:?}", stop),
+    }
+    assert_eq!(d.listing(), "5\n");
+}
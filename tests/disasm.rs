@@ -0,0 +1,65 @@
+extern crate gapush;
+
+use gapush::Machine;
+use gapush::simple::*;
+
+const PROGRAM: &str = "( 3 4 Addi64 ( Inci64 ) CreateLoop ) loop( Nop -2 ) if( true 'c' )( \"a \\\"b\\\"\\n\" ) \
+                       i64[1 -2] f64[0.5 1.0] b[true] 2.5 -0.0 name:1 tag:2 ref:3 while( Notb ) times@1:3( Nop ) \
+                       range@4:10( Nop ) range( ) times( Dupi64 ) itimes@0:2( ) itimes( Nop )";
+
+/// Parse and disassemble `src`, checking that the disassembly parses back into the same program.
+fn round_trip(src: &str) -> String {
+    let text = disassemble(&parse(src).unwrap());
+    assert_eq!(disassemble(&parse(&text).unwrap()), text);
+    text
+}
+
+#[test]
+fn round_trips() {
+    let text = round_trip(PROGRAM);
+    assert!(text.contains("if(true 'c')(\"a \\\"b\\\"\\n\")"), "{}", text);
+    assert!(text.contains("times@1:3(Nop)"), "{}", text);
+    assert!(text.contains("itimes@0:2()") && text.contains("itimes(Nop)"), "{}", text);
+    assert!(text.contains("range@4:10(Nop)"), "{}", text);
+    // Floats keep a decimal point so they don't turn into ints.
+    assert!(text.contains("1.0") && text.contains("-0.0"), "{}", text);
+}
+
+#[test]
+fn long_and_nested_blocks_are_indented() {
+    let long = format!("( {} )", vec!["Addi64"; 30].join(" "));
+    let text = round_trip(&long);
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines.first(), Some(&"("));
+    assert_eq!(lines.last(), Some(&")"));
+    assert!(lines[1..lines.len() - 1].iter().all(|l| l.starts_with("    ") && l.len() <= 80));
+
+    let text = round_trip("loop( 1 ( 2 ) )");
+    assert_eq!(text, "loop(\n    1\n    (2)\n)\n");
+}
+
+#[test]
+fn display_forms() {
+    let ins: SimpleInstruction = "( 1 ( 2 ) )".parse().unwrap();
+    assert_eq!(format!("{}", ins), "(1 (2))");
+    assert_eq!(format!("{:#}", ins), "(\n    1\n    (2)\n)");
+    assert_eq!(PlainOp::Addi64.to_string(), "Addi64");
+
+    let chromosome = Chromosome::new(vec![Gene::Int(1), Gene::Open(Block::Loop), Gene::Op(PlainOp::Inci64)],
+                                     Default::default());
+    assert_eq!(chromosome.to_string(), "(1 loop(Inci64))");
+}
+
+#[test]
+fn partially_executed_instructions_continue_where_they_left_off() {
+    let src = "( 1 2 3 ) loop( 4 5 6 )";
+    let mut machine = Machine::new(1 << 16, || SimpleInstruction::PlainOp(PlainOp::Nop), || 0, || 0.0);
+    for ins in parse(src).unwrap().into_iter().rev() {
+        machine.provide(ins);
+    }
+    // Each element of the block takes two cycles to run, then the loop takes two more to push 4.
+    machine.run(8);
+    assert_eq!(machine.state.int_stack(), &[1, 2, 3, 4]);
+    let remaining = machine.state.exe_stack().iter().rev().cloned().collect::<Vec<_>>();
+    assert_eq!(disassemble(&remaining), "loop(5 6 4)\n");
+}
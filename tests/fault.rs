@@ -0,0 +1,80 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with};
+use gapush::{Fault, SizeError};
+use gapush::simple::*;
+
+/// Provide and execute each instruction of `src` in turn, returning the result of the last one.
+fn last_fault(m: &mut TestMachine, src: &str) -> Result<(), Fault> {
+    let program = parse(src).unwrap();
+    let mut result = Ok(());
+    for ins in program {
+        m.provide(ins);
+        result = m.cycle().1;
+    }
+    result
+}
+
+#[test]
+fn handled_faults_still_take_effect() {
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "Addi64"), Err(Fault::Underflow));
+    assert_eq!(m.state.int_stack(), &[14]);
+
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "1 0 Divi64"), Err(Fault::Undefined));
+    assert_eq!(m.state.int_stack(), &[7]);
+
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "Incf64"), Err(Fault::Underflow));
+    assert_eq!(m.state.float_stack(), &[1.5]);
+
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "-3 -3 Powi64"), Ok(()));
+    assert_eq!(last_fault(&mut m, "2 63 Powi64"), Err(Fault::Undefined));
+    // The exponent's magnitude masks to zero.
+    assert_eq!(last_fault(&mut m, "-9223372036854775808 Powi64"), Ok(()));
+    assert_eq!(last_fault(&mut m, "-9223372036854775808 Absi64"), Err(Fault::Undefined));
+    assert_eq!(m.state.int_stack(), &[-27, 1, 7]);
+
+    assert!(Fault::Underflow.is_handled() && Fault::Undefined.is_handled());
+}
+
+#[test]
+fn unhandled_faults_do_nothing() {
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "Popi64"), Err(Fault::Empty));
+    assert_eq!(last_fault(&mut m, "1 5 Copyi64"), Err(Fault::OutOfRange));
+    assert_eq!(m.state.int_stack(), &[1]);
+    assert_eq!(last_fault(&mut m, "99999 CreatePlain"), Err(Fault::InvalidOpcode));
+    assert!(m.state.ins_stack().is_empty());
+
+    for &fault in &[Fault::Empty, Fault::Full, Fault::OutOfRange, Fault::InvalidOpcode, Fault::TypeMismatch] {
+        assert!(!fault.is_handled());
+    }
+}
+
+#[test]
+fn quoting_something_other_than_a_name_is_a_type_mismatch() {
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    m.provide(SimpleInstruction::Pushi64(3));
+    m.provide(SimpleInstruction::PlainOp(PlainOp::Quotename));
+    assert_eq!(m.cycle().1, Err(Fault::TypeMismatch));
+    // The instruction is left to run.
+    assert_eq!(m.state.exe_len(), 1);
+    assert!(m.state.name_stack().is_empty());
+
+    let mut m = machine_with(1 << 16, || 7, || 0.5);
+    assert_eq!(last_fault(&mut m, "Quotename"), Err(Fault::Empty));
+}
+
+#[test]
+fn running_out_of_memory_is_full() {
+    let mut m = machine_with(1 << 10, || 7, || 0.5);
+    m.state.push_int_vecs(&[&[0; 100]]).unwrap();
+    assert_eq!(last_fault(&mut m, "Dupi64v"), Err(Fault::Full));
+    assert_eq!(m.state.int_vec_len(), 1);
+    assert_eq!(Fault::from(SizeError::Full), Fault::Full);
+}
@@ -0,0 +1,130 @@
+extern crate gapush;
+extern crate rand;
+
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([1, 2, 3, 4])
+}
+
+/// Perturb `gene` `times` times, collecting each result.
+fn perturbed(gene: &Gene, int_delta: i64, float_deviation: f64, times: usize) -> Vec<Gene> {
+    let mut rng = rng();
+    (0..times)
+        .map(|_| {
+            let mut g = gene.clone();
+            g.perturb(int_delta, float_deviation, &mut rng);
+            g
+        })
+        .collect()
+}
+
+#[test]
+fn ints_move_by_up_to_the_delta() {
+    let moved: Vec<i64> = perturbed(&Gene::Int(10), 2, 0.1, 500)
+        .into_iter()
+        .map(|g| match g {
+            Gene::Int(n) => n,
+            g => panic!("{:?}", g),
+        })
+        .collect();
+    assert!(moved.iter().all(|&n| (8..13).contains(&n)));
+    assert!(moved.contains(&8) && moved.contains(&12));
+
+    // The value wraps rather than overflowing, and the widest delta is usable.
+    for g in perturbed(&Gene::Int(i64::MAX), i64::MAX, 0.1, 50) {
+        assert!(matches!(g, Gene::Int(_)));
+    }
+}
+
+#[test]
+fn floats_move_by_a_gaussian() {
+    let moved: Vec<f64> = perturbed(&Gene::Float(1.0), 2, 0.1, 500)
+        .into_iter()
+        .map(|g| match g {
+            Gene::Float(n) => n,
+            g => panic!("{:?}", g),
+        })
+        .collect();
+    assert!(moved.iter().all(|&n| n != 1.0 && (n - 1.0).abs() < 1.0));
+    let mean = moved.iter().sum::<f64>() / moved.len() as f64;
+    assert!((mean - 1.0).abs() < 0.05, "{}", mean);
+}
+
+#[test]
+fn negative_perturbation_leaves_constants_alone() {
+    for gene in &[Gene::Int(3), Gene::Float(0.5), Gene::Tag(4)] {
+        assert!(perturbed(gene, -3, -1.0, 50).iter().all(|g| g == gene), "{:?}", gene);
+    }
+    assert!(perturbed(&Gene::Float(0.5), 0, f64::NAN, 50).iter().all(|g| *g == Gene::Float(0.5)));
+}
+
+#[test]
+fn bools_flip() {
+    assert_eq!(perturbed(&Gene::Bool(true), 2, 0.1, 1), [Gene::Bool(false)]);
+    let moved = perturbed(&Gene::BoolVec(vec![false, false, false]), 2, 0.1, 50);
+    for g in moved {
+        match g {
+            Gene::BoolVec(v) => assert_eq!(v.iter().filter(|&&b| b).count(), 1),
+            g => panic!("{:?}", g),
+        }
+    }
+}
+
+#[test]
+fn vectors_change_one_element() {
+    for g in perturbed(&Gene::IntVec(vec![0; 4]), 2, 0.1, 200) {
+        match g {
+            Gene::IntVec(v) => {
+                assert_eq!(v.len(), 4);
+                assert!(v.iter().filter(|&&n| n != 0).count() <= 1);
+                assert!(v.iter().all(|&n| (-2..3).contains(&n)));
+            }
+            g => panic!("{:?}", g),
+        }
+    }
+    for g in perturbed(&Gene::FloatVec(vec![0.0; 3]), 2, 0.1, 50) {
+        match g {
+            Gene::FloatVec(v) => assert_eq!(v.iter().filter(|&&n| n != 0.0).count(), 1),
+            g => panic!("{:?}", g),
+        }
+    }
+    assert_eq!(perturbed(&Gene::IntVec(vec![]), 2, 0.1, 1), [Gene::IntVec(vec![])]);
+}
+
+#[test]
+fn tags_stay_in_range() {
+    for g in perturbed(&Gene::Tag(0), 5, 0.1, 100) {
+        match g {
+            Gene::Tag(n) => assert!(n <= 5),
+            g => panic!("{:?}", g),
+        }
+    }
+    for g in perturbed(&Gene::TagRef(u32::MAX), 5, 0.1, 100) {
+        match g {
+            Gene::TagRef(n) => assert!(n >= u32::MAX - 5),
+            g => panic!("{:?}", g),
+        }
+    }
+}
+
+#[test]
+fn genes_without_constants_are_left_alone() {
+    for gene in &[Gene::Op(PlainOp::Addi64), Gene::Open(Block::Loop), Gene::Close] {
+        assert_eq!(&perturbed(gene, 2, 0.1, 1)[0], gene);
+    }
+}
+
+#[test]
+fn empty_literal_ranges_produce_the_low_end() {
+    let mut rng = rng();
+    assert_eq!(Literal::Int { low: 5, high: 5 }.generate(&mut rng), Gene::Int(5));
+    assert_eq!(Literal::Int { low: 5, high: -5 }.generate(&mut rng), Gene::Int(5));
+    assert_eq!(Literal::Float { low: 1.0, high: 0.0 }.generate(&mut rng), Gene::Float(1.0));
+    assert_eq!(Literal::Tag { low: 3, high: 3 }.generate(&mut rng), Gene::Tag(3));
+    match (Literal::IntVec { low: 2, high: 2, max_len: 3 }).generate(&mut rng) {
+        Gene::IntVec(v) => assert!(v.iter().all(|&n| n == 2)),
+        g => panic!("{:?}", g),
+    }
+}
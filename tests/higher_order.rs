@@ -0,0 +1,76 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine, machine_with};
+use gapush::simple::*;
+
+/// Run the top level instructions of `src` in order, checking that nothing failed.
+///
+/// `Yield` takes the next of these instructions, so a function can be written right after it.
+fn run(src: &str) -> TestMachine {
+    let mut m = machine(src);
+    let outcome = m.run(10_000);
+    assert_eq!(outcome.failures, 0, "{}", src);
+    m
+}
+
+fn int_vecs(m: &TestMachine) -> Vec<&[i64]> {
+    m.state.int_vec_stack().iter().map(|v| v.as_slice()).collect()
+}
+
+fn float_vecs(m: &TestMachine) -> Vec<&[f64]> {
+    m.state.float_vec_stack().iter().map(|v| v.as_slice()).collect()
+}
+
+#[test]
+fn map() {
+    let m = run("Yield ( 2 Muli64 ) i64[1 2 3] Mapvi64");
+    assert_eq!(int_vecs(&m), [&[2, 4, 6][..]]);
+    assert!(m.state.int_stack().is_empty());
+
+    let m = run("Yield Negf64 f64[0.5 -1.0] Mapvf64");
+    assert_eq!(float_vecs(&m), [&[-0.5, 1.0][..]]);
+
+    let m = run("Yield Negi64 i64[] Mapvi64");
+    assert_eq!(int_vecs(&m), [&[][..]]);
+}
+
+#[test]
+fn filter() {
+    let m = run("Yield ( 2 Grti64 ) i64[1 5 2 3] Filtervi64");
+    assert_eq!(int_vecs(&m), [&[5, 3][..]]);
+
+    let m = run("Yield ( 0.0 Lesf64 ) f64[1.0 -2.0 0.5] Filtervf64");
+    assert_eq!(float_vecs(&m), [&[-2.0][..]]);
+}
+
+#[test]
+fn fold() {
+    let m = run("0 Yield Addi64 i64[1 2 3 4] Foldvi64");
+    assert_eq!(m.state.int_stack(), &[10]);
+    assert!(m.state.int_vec_stack().is_empty());
+
+    let m = run("1.0 Yield Mulf64 f64[2.0 0.25] Foldvf64");
+    assert_eq!(m.state.float_stack(), &[0.5]);
+}
+
+#[test]
+fn zip() {
+    let m = run("Yield Subi64 i64[10 20 30] i64[1 2] Zipvi64");
+    assert_eq!(int_vecs(&m), [&[9, 18][..]]);
+
+    let m = run("Yield Addf64 f64[0.5] f64[0.25 4.0] Zipvf64");
+    assert_eq!(float_vecs(&m), [&[0.75][..]]);
+}
+
+#[test]
+fn expansion_is_bounded_by_memory() {
+    let mut m = machine_with(1 << 12, || 0, || 0.0);
+    m.state.push_ins(SimpleInstruction::PlainOp(PlainOp::Negi64)).unwrap();
+    m.state.push_int_vecs(&[&[0; 256]]).unwrap();
+    m.provide(SimpleInstruction::PlainOp(PlainOp::Mapvi64));
+    let outcome = m.run(10_000);
+    assert!(outcome.failures > 0);
+    assert!(outcome.peak_memory <= 1 << 12);
+}
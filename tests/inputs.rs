@@ -0,0 +1,59 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with, provide};
+use gapush::Fault;
+
+/// A machine whose int handler supplies 1 and whose inputs hold a few values of every kind.
+fn machine(src: &str) -> TestMachine {
+    let mut machine = machine_with(1 << 16, || 1, || 0.0);
+    machine.inputs.ints = vec![10, 11];
+    machine.inputs.floats = vec![0.5];
+    machine.inputs.bools = vec![true, false];
+    machine.inputs.int_vecs = vec![vec![1, 2], vec![3]];
+    machine.inputs.float_vecs = vec![vec![]];
+    machine.inputs.bool_vecs = vec![vec![true]];
+    provide(&mut machine, src);
+    machine
+}
+
+#[test]
+fn fixed_registers() {
+    let mut m = machine("( In1i64 In0i64 In1i64 In0f64 In1b In1i64v In0f64v In0bv )");
+    assert_eq!(m.run(100).failures, 0);
+    assert_eq!(m.state.int_stack(), &[11, 10, 11]);
+    assert_eq!(m.state.float_stack(), &[0.5]);
+    assert_eq!(m.state.bool_stack(), &[false]);
+    assert_eq!(m.state.int_vec_stack()[0].as_slice(), &[3]);
+    assert!(m.state.float_vec_stack()[0].is_empty());
+    assert_eq!(m.state.bool_vec_stack()[0].as_slice(), &[true]);
+    // Reading a register doesn't consume it.
+    assert_eq!(m.inputs.ints, vec![10, 11]);
+}
+
+#[test]
+fn missing_registers_are_out_of_range() {
+    let mut m = machine("In2i64");
+    assert_eq!(m.cycle().1, Err(Fault::OutOfRange));
+    let mut m = machine("In1f64");
+    assert_eq!(m.cycle().1, Err(Fault::OutOfRange));
+    assert!(m.state.float_stack().is_empty());
+}
+
+#[test]
+fn indexed_registers() {
+    let mut m = machine("( 0 Ini64 0 Inb 0 Ini64v 2 Ini64v )");
+    let outcome = m.run(100);
+    assert_eq!((outcome.failures, outcome.handled), (1, 0));
+    assert_eq!(m.state.int_stack(), &[10]);
+    assert_eq!(m.state.bool_stack(), &[true]);
+    assert_eq!(m.state.int_vec_stack()[0].as_slice(), &[1, 2]);
+
+    // With no index on the stack, the int handler supplies one.
+    let mut m = machine("( Inb Ini64 )");
+    let outcome = m.run(100);
+    assert_eq!((outcome.failures, outcome.handled), (0, 2));
+    assert_eq!(m.state.int_stack(), &[11]);
+    assert_eq!(m.state.bool_stack(), &[false]);
+}
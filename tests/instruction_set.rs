@@ -0,0 +1,110 @@
+extern crate gapush;
+extern crate rand;
+
+use gapush::Stack;
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+
+const TRIALS: usize = 8000;
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([1, 2, 3, 4])
+}
+
+/// Draw `TRIALS` operations from `set`, counting how many times `op` comes up.
+fn draws(set: &InstructionSet, op: PlainOp) -> usize {
+    let mut rng = rng();
+    (0..TRIALS).filter(|_| set.gen_op(&mut rng) == op).count()
+}
+
+#[test]
+fn empty_and_all() {
+    let empty = InstructionSet::empty();
+    assert!(empty.ops().is_empty() && empty.literals().is_empty() && empty.blocks().is_empty());
+
+    let all = InstructionSet::all();
+    assert_eq!(all.ops().len(), PlainOp::ALL.len());
+    assert!(PlainOp::ALL.iter().all(|&op| all.weight(op) == 1.0));
+    assert_eq!(InstructionSet::default(), all);
+}
+
+#[test]
+fn building_weights() {
+    let set = InstructionSet::empty()
+        .with_op(PlainOp::Addi64, 2.0)
+        .with_op(PlainOp::Addi64, 3.0)
+        .with_category(Category::Float, 0.5)
+        .without_op(PlainOp::Negf64);
+    assert_eq!(set.weight(PlainOp::Addi64), 3.0);
+    assert_eq!(set.ops().iter().filter(|&&(op, _)| op == PlainOp::Addi64).count(), 1);
+    assert_eq!(set.weight(PlainOp::Addf64), 0.5);
+    assert_eq!(set.weight(PlainOp::Negf64), 0.0);
+    assert_eq!(set.weight(PlainOp::Subi64), 0.0);
+
+    let set = set.without_category(Category::Float);
+    assert_eq!(set.ops(), &[(PlainOp::Addi64, 3.0)]);
+
+    let set = InstructionSet::all().without_stack(Stack::Float);
+    assert!(set.ops().iter().all(|&(op, _)| !op.pops().contains(&Stack::Float) && !op.pushes().contains(&Stack::Float)));
+    assert_eq!(set.weight(PlainOp::Addf64), 0.0);
+    assert_eq!(set.weight(PlainOp::Addi64), 1.0);
+}
+
+#[test]
+fn ops_are_drawn_in_proportion_to_their_weights() {
+    let set = InstructionSet::empty()
+        .with_op(PlainOp::Addi64, 3.0)
+        .with_op(PlainOp::Subi64, 1.0)
+        .with_op(PlainOp::Muli64, 0.0)
+        .with_op(PlainOp::Divi64, -5.0);
+    let adds = draws(&set, PlainOp::Addi64);
+    assert!(adds > TRIALS * 7 / 10 && adds < TRIALS * 8 / 10, "{}", adds);
+    // Zero and negative weights are never drawn.
+    assert_eq!(draws(&set, PlainOp::Muli64), 0);
+    assert_eq!(draws(&set, PlainOp::Divi64), 0);
+}
+
+#[test]
+fn genes_are_drawn_in_proportion_to_their_kinds() {
+    let set = InstructionSet::empty()
+        .with_op(PlainOp::Addi64, 1.0)
+        .with_literal(Literal::Int { low: 0, high: 3 }, 2.0)
+        .with_block(Block::Basic, 0.5)
+        .with_block(Block::Loop, 0.5);
+    let mut rng = rng();
+    let (mut ops, mut ints, mut opens, mut closes) = (0, 0, 0, 0);
+    for _ in 0..TRIALS {
+        match set.gen_gene(&mut rng) {
+            Gene::Op(PlainOp::Addi64) => ops += 1,
+            Gene::Int(n) => {
+                assert!((0..3).contains(&n));
+                ints += 1;
+            }
+            Gene::Open(Block::Basic) | Gene::Open(Block::Loop) => opens += 1,
+            Gene::Close => closes += 1,
+            gene => panic!("{:?}", gene),
+        }
+    }
+    // Weights of 1, 2, 1 and 1 for the ops, literals, opens and closes.
+    for &(count, share) in &[(ops, 1), (ints, 2), (opens, 1), (closes, 1)] {
+        let expected = TRIALS * share / 5;
+        assert!(count > expected * 9 / 10 && count < expected * 11 / 10, "{} {}", count, expected);
+    }
+}
+
+#[test]
+fn instructions_leave_out_blocks() {
+    let set = InstructionSet::empty()
+        .with_literal(Literal::Bool, 1.0)
+        .with_block(Block::Basic, 100.0);
+    let mut rng = rng();
+    for _ in 0..100 {
+        assert!(matches!(set.gen_instruction(&mut rng), SimpleInstruction::Pushb(_)));
+    }
+}
+
+#[test]
+#[should_panic]
+fn drawing_from_an_empty_set_panics() {
+    InstructionSet::empty().gen_op(&mut rng());
+}
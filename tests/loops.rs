@@ -0,0 +1,106 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with, new_machine, provide};
+use gapush::{Termination, TrackedVec};
+use gapush::simple::*;
+
+/// Run `src` with an int handler which supplies 2, returning the machine and the number of handled instructions.
+fn run(src: &str) -> (TestMachine, usize) {
+    let mut m = machine_with(1 << 16, || 2, || 0.0);
+    provide(&mut m, src);
+    let outcome = m.run(10_000);
+    match outcome.termination {
+        Termination::Halted => {}
+        t => panic!("`{}` didn't halt: {:?}", src, t),
+    }
+    assert_eq!(outcome.failures, 0, "{}", src);
+    (m, outcome.handled)
+}
+
+#[test]
+fn do_times() {
+    let (m, _) = run("0 3 times( 1 Addi64 )");
+    assert_eq!(m.state.int_stack(), &[3]);
+
+    let (m, _) = run("4 itimes( )");
+    assert_eq!(m.state.int_stack(), &[0, 1, 2, 3]);
+
+    // A count which isn't positive runs nothing.
+    let (m, _) = run("-1 itimes( ) 0 itimes( )");
+    assert!(m.state.int_stack().is_empty());
+
+    // A missing count comes from the int handler.
+    let (m, handled) = run("itimes( )");
+    assert_eq!(handled, 1);
+    assert_eq!(m.state.int_stack(), &[0, 1]);
+}
+
+#[test]
+fn do_times_resumes_from_its_state() {
+    let (m, _) = run("itimes@2:4( )");
+    assert_eq!(m.state.int_stack(), &[2, 3]);
+}
+
+#[test]
+fn do_range() {
+    let (m, _) = run("1 3 range( )");
+    assert_eq!(m.state.int_stack(), &[1, 2, 3]);
+
+    let (m, _) = run("2 -1 range( Popi64 )");
+    assert!(m.state.int_stack().is_empty());
+
+    let (m, _) = run("5 5 range( )");
+    assert_eq!(m.state.int_stack(), &[5]);
+
+    let (m, handled) = run("range( )");
+    assert_eq!(handled, 1);
+    assert_eq!(m.state.int_stack(), &[2]);
+}
+
+#[test]
+fn exe_loops() {
+    let (m, _) = run("3 DoTimesexe 7");
+    assert_eq!(m.state.int_stack(), &[7, 7, 7]);
+
+    let (m, _) = run("0 2 DoRangeexe Inci64");
+    assert_eq!(m.state.int_stack(), &[1, 2, 3]);
+
+    let (m, handled) = run("DoTimesexe 7");
+    assert_eq!(handled, 1);
+    assert_eq!(m.state.int_stack(), &[7, 7]);
+}
+
+#[test]
+fn constructed_loops() {
+    let mut m = new_machine();
+    m.state.push_ins_vec(TrackedVec::new()).unwrap();
+    m.provide("( 3 CreateDoTimesIndexed Call )".parse().unwrap());
+    assert_eq!(m.run(100).failures, 0);
+    assert_eq!(m.state.int_stack(), &[0, 1, 2]);
+}
+
+#[test]
+fn while_loops() {
+    let (m, _) = run("true 3 while( Deci64 Dupi64 0 Grti64 )");
+    assert_eq!(m.state.int_stack(), &[0]);
+    assert!(m.state.bool_stack().is_empty());
+
+    let (m, _) = run("false while( 1 )");
+    assert!(m.state.int_stack().is_empty());
+
+    // With no bool to pop, the loop stops.
+    let (m, handled) = run("while( 1 )");
+    assert_eq!(handled, 1);
+    assert!(m.state.int_stack().is_empty());
+
+    let mut m = new_machine();
+    let body = vec![SimpleInstruction::Pushi64(1), SimpleInstruction::PlainOp(PlainOp::Notb)];
+    m.state.push_ins_vec(TrackedVec::new_from_vec(body)).unwrap();
+    m.provide("( true true CreateWhile Call )".parse().unwrap());
+    assert_eq!(m.run(100).failures, 0);
+    // The body ran once, turning the second true into false.
+    assert_eq!(m.state.int_stack(), &[1]);
+    assert!(m.state.bool_stack().is_empty());
+}
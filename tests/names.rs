@@ -0,0 +1,74 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine};
+use gapush::simple::*;
+
+/// Run `src` to completion, checking that nothing failed.
+///
+/// `Quotename` quotes the next top level instruction of `src`, so it can't be used inside a block.
+fn run(src: &str) -> TestMachine {
+    let mut m = machine(src);
+    assert_eq!(m.run(1000).failures, 0, "{}", src);
+    m
+}
+
+#[test]
+fn unbound_names_are_pushed() {
+    let m = run("( name:1 name:2 3 Iton )");
+    assert_eq!(m.state.name_stack(), &[1, 2, 3]);
+    assert!(m.state.bindings().is_empty());
+}
+
+#[test]
+fn bound_names_run_their_binding() {
+    let m = run("( 5 name:1 Definei64 name:1 name:1 Addi64 )");
+    assert_eq!(m.state.int_stack(), &[10]);
+    assert!(m.state.name_stack().is_empty());
+
+    let m = run("( 'c' name:2 Definec \"s\" name:3 Defines true name:4 Defineb 0.5 name:5 Definef64 \
+                 name:2 name:3 name:4 name:5 )");
+    assert_eq!(m.state.char_stack(), &['c']);
+    assert_eq!(m.state.string_stack(), &["s".to_string()]);
+    assert_eq!(m.state.bool_stack(), &[true]);
+    assert_eq!(m.state.float_stack(), &[0.5]);
+
+    // Binding a name again replaces its binding.
+    let m = run("1 name:1 Definei64 2 Quotename name:1 Definei64 name:1");
+    assert_eq!(m.state.int_stack(), &[2]);
+    assert_eq!(m.state.bindings().len(), 1);
+}
+
+#[test]
+fn quoting_pushes_bound_names() {
+    let m = run("5 name:1 Definei64 Quotename name:1 name:1 CreateName");
+    assert_eq!(m.state.int_stack(), &[5]);
+    match m.state.ins_stack() {
+        [SimpleInstruction::Name(1)] => {}
+        ins => panic!("{:?}", ins),
+    }
+}
+
+#[test]
+fn name_stack_manipulation() {
+    let m = run("( name:1 name:2 name:3 2 Rotn 0 Copyn Popn Swapn Dupn Depthn )");
+    assert_eq!(m.state.name_stack(), &[2, 1, 3, 3]);
+    assert_eq!(m.state.int_stack(), &[4]);
+}
+
+#[test]
+fn flushing_bindings_releases_their_memory() {
+    let mut m = machine("i64[1 2 3 4 5 6 7 8] CreatePushi64v name:1 Defineins");
+    assert_eq!(m.run(100).failures, 0);
+    assert_eq!(m.state.bindings().len(), 1);
+    assert!(m.state.size() > 8 * 8);
+
+    m.provide("name:1".parse().unwrap());
+    m.provide("Flushbindings".parse().unwrap());
+    assert_eq!(m.run(100).failures, 0);
+    assert!(m.state.bindings().is_empty());
+    assert_eq!(m.state.name_stack(), &[1]);
+    m.state.flush_name();
+    assert_eq!(m.state.size(), 0);
+}
@@ -0,0 +1,92 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with};
+use gapush::{Stack, TrackedVec};
+use gapush::simple::*;
+
+const STACKS: &[Stack] = &[Stack::Exe,
+                           Stack::Ins,
+                           Stack::Int,
+                           Stack::Float,
+                           Stack::Bool,
+                           Stack::String,
+                           Stack::Char,
+                           Stack::Name,
+                           Stack::InsVec,
+                           Stack::IntVec,
+                           Stack::FloatVec,
+                           Stack::BoolVec];
+
+/// A machine with enough valid operands on every stack and in every input register for any operation to succeed.
+fn stocked_machine() -> TestMachine {
+    let mut m = machine_with(1 << 20, || 1, || 1.0);
+    for _ in 0..4 {
+        m.state.push_exe(SimpleInstruction::Name(1)).unwrap();
+        m.state.push_ins(SimpleInstruction::Pushi64(1)).unwrap();
+        m.state.push_ins_vec(TrackedVec::new_from_vec(vec![SimpleInstruction::Pushi64(1); 2])).unwrap();
+        m.state.push_name(1).unwrap();
+    }
+    m.state.push_ints(&[1; 4]).unwrap();
+    m.state.push_floats(&[1.0; 4]).unwrap();
+    m.state.push_bools(&[true; 4]).unwrap();
+    m.state.push_strings(&["ab"; 4]).unwrap();
+    m.state.push_chars(&['a'; 4]).unwrap();
+    m.state.push_int_vecs(&[&[1, 1][..]; 4]).unwrap();
+    m.state.push_float_vecs(&[&[1.0, 1.0][..]; 4]).unwrap();
+    m.state.push_bool_vecs(&[&[true, true][..]; 4]).unwrap();
+    m.inputs.ints = vec![1; 4];
+    m.inputs.floats = vec![1.0; 4];
+    m.inputs.bools = vec![true; 4];
+    m.inputs.int_vecs = vec![vec![1]; 4];
+    m.inputs.float_vecs = vec![vec![1.0]; 4];
+    m.inputs.bool_vecs = vec![vec![true]; 4];
+    m
+}
+
+/// Whether the number of items the operation moves isn't fixed, so the table leaves them out.
+fn moves_variable_items(op: PlainOp) -> bool {
+    op.name().starts_with("Flush") || op == PlainOp::Splits
+}
+
+#[test]
+fn stack_effects_match_execution() {
+    let mut mismatches = Vec::new();
+    for &op in PlainOp::ALL.iter().filter(|&&op| !moves_variable_items(op)) {
+        let mut m = stocked_machine();
+        let before = m.state.depths();
+        m.provide(SimpleInstruction::PlainOp(op));
+        let (_, result) = m.cycle();
+        assert!(result.is_ok(), "{:?} failed with {:?}", op, result);
+        let after = m.state.depths();
+        for &stack in STACKS {
+            let count = |stacks: &[Stack]| stacks.iter().filter(|&&s| s == stack).count() as isize;
+            let expected = count(op.pushes()) - count(op.pops());
+            let actual = after.get(stack) as isize - before.get(stack) as isize;
+            if actual != expected {
+                mismatches.push(format!("{:?} changes the {:?} stack by {}, not {}", op, stack, actual, expected));
+            }
+        }
+    }
+    assert!(mismatches.is_empty(), "\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn codes_round_trip() {
+    for (ix, &op) in PlainOp::ALL.iter().enumerate() {
+        assert_eq!(op.to_code() as usize, ix);
+        assert_eq!(PlainOp::from_code(op.to_code()), Some(op));
+    }
+    assert_eq!(PlainOp::from_code(PlainOp::ALL.len() as u16), None);
+    assert_eq!(PlainOp::from_code(u16::MAX), None);
+}
+
+#[test]
+fn exe_stack_operations_are_control() {
+    for &op in PlainOp::ALL {
+        if op.name().ends_with("exe") {
+            assert_eq!(op.category(), Category::Control, "{:?}", op);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+extern crate gapush;
+extern crate rand;
+
+use gapush::Machine;
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+
+fn rng() -> XorShiftRng {
+    XorShiftRng::from_seed([1, 2, 3, 4])
+}
+
+fn gene(gene: Gene, close: u32) -> PlushGene {
+    PlushGene {
+        close,
+        ..PlushGene::new(gene)
+    }
+}
+
+fn silent(gene: Gene, close: u32) -> PlushGene {
+    PlushGene {
+        gene,
+        close,
+        silent: true,
+    }
+}
+
+fn translate(genes: Vec<PlushGene>) -> String {
+    Plush::new(genes).to_string()
+}
+
+#[test]
+fn closes_end_block_bodies() {
+    assert_eq!(translate(vec![gene(Gene::Open(Block::Loop), 0), gene(Gene::Int(1), 1), gene(Gene::Int(2), 0)]),
+               "(loop(1) 2)");
+    // Each body of an if takes a close of its own.
+    assert_eq!(translate(vec![gene(Gene::Open(Block::If), 0),
+                              gene(Gene::Bool(true), 1),
+                              gene(Gene::Int(1), 1),
+                              gene(Gene::Int(2), 0)]),
+               "(if(true)(1) 2)");
+    // One gene can end several nested blocks.
+    assert_eq!(translate(vec![gene(Gene::Open(Block::Loop), 0),
+                              gene(Gene::Open(Block::While), 0),
+                              gene(Gene::Int(1), 2),
+                              gene(Gene::Int(2), 0)]),
+               "(loop(while(1)) 2)");
+}
+
+#[test]
+fn unmatched_closes_and_opens() {
+    // Closes with no open block are ignored, and blocks left open end with the genome.
+    assert_eq!(translate(vec![gene(Gene::Int(1), 5), gene(Gene::Open(Block::Loop), 0), gene(Gene::Int(2), 0)]),
+               "(1 loop(2))");
+    // Close genes end a body as well.
+    assert_eq!(translate(vec![gene(Gene::Open(Block::Loop), 0),
+                              gene(Gene::Int(1), 0),
+                              gene(Gene::Close, 0),
+                              gene(Gene::Int(2), 0)]),
+               "(loop(1) 2)");
+    assert_eq!(translate(vec![]), "()");
+}
+
+#[test]
+fn silent_genes_are_skipped_with_their_closes() {
+    assert_eq!(translate(vec![gene(Gene::Open(Block::Loop), 0), silent(Gene::Int(1), 1), gene(Gene::Int(2), 0)]),
+               "(loop(2))");
+    assert_eq!(translate(vec![silent(Gene::Open(Block::Loop), 0), gene(Gene::Int(1), 1), gene(Gene::Int(2), 0)]),
+               "(1 2)");
+}
+
+#[test]
+fn translations_run() {
+    let plush = Plush::new(vec![gene(Gene::Int(3), 0),
+                                gene(Gene::Open(Block::DoTimes), 0),
+                                gene(Gene::Int(2), 1),
+                                gene(Gene::Op(PlainOp::Addi64), 0),
+                                silent(Gene::Op(PlainOp::Negi64), 0)]);
+    let mut m = Machine::new(1 << 16,
+                             || SimpleInstruction::PlainOp(PlainOp::Nop),
+                             || 0,
+                             || 0.0);
+    m.provide((&plush).into());
+    assert_eq!(m.run(100).failures, 0);
+    assert_eq!(m.state.int_stack(), &[2, 4]);
+}
+
+#[test]
+fn random_genomes() {
+    let set = InstructionSet::empty()
+        .with_op(PlainOp::Nop, 1.0)
+        .with_block(Block::Loop, 1.0);
+    let plush = Plush::new_rand(&mut rng(), &set, 50, 0.0, 0.0);
+    assert_eq!(plush.len(), 50);
+    assert!(plush.genes.iter().all(|g| g.close == 0 && !g.silent));
+
+    let plush = Plush::new_rand(&mut rng(), &set, 50, 0.5, 1.0);
+    assert!(plush.genes.iter().all(|g| g.silent));
+    assert!(plush.genes.iter().any(|g| g.close > 0));
+    assert_eq!(plush.to_string(), "()");
+
+    // A close rate of 1 or more would close forever without the cap.
+    let plush = Plush::new_rand(&mut rng(), &set, 10, 1.0, 0.0);
+    assert!(plush.genes.iter().all(|g| g.close == MAX_CLOSE));
+}
+
+#[test]
+fn mutation() {
+    let set = InstructionSet::empty().with_op(PlainOp::Nop, 1.0);
+    let original = Plush::new(vec![gene(Gene::Int(0), 0); 50]);
+
+    let mut plush = original.clone();
+    plush.mutate(0.0, 0.5, &set, &mut rng());
+    assert_eq!(plush, original);
+
+    let mut plush = original.clone();
+    plush.mutate(1.0, 1.0, &set, &mut rng());
+    assert_eq!(plush.len(), 50);
+    assert!(plush.genes.iter().any(|g| g.gene == Gene::Op(PlainOp::Nop)));
+    assert!(plush.genes.iter().any(|g| g.close == MAX_CLOSE));
+    assert!(plush.genes.iter().any(|g| g.silent));
+    assert!(plush.genes.iter().any(|g| matches!(g.gene, Gene::Int(n) if n != 0)));
+}
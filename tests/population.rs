@@ -0,0 +1,131 @@
+extern crate gapush;
+extern crate rand;
+
+mod common;
+
+use common::{TestMachine, machine_with, new_machine};
+use gapush::simple::*;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use std::cell::Cell;
+
+/// Scores how close the int left on top of the stack is to 10.
+fn fitness(machine: &mut TestMachine) -> f64 {
+    machine.run(200);
+    match machine.state.int_stack().last() {
+        Some(&n) => -(n.wrapping_sub(10) as f64).abs(),
+        None => -1000.0,
+    }
+}
+
+fn config() -> PopulationConfig {
+    PopulationConfig {
+        instructions: InstructionSet::empty()
+            .with_category(Category::Integer, 1.0)
+            .with_literal(Literal::Int { low: 0, high: 5 }, 1.0),
+        ..PopulationConfig::default()
+    }
+}
+
+fn population(seed: u32) -> Population<XorShiftRng> {
+    Population::new_rand(XorShiftRng::from_seed([seed, 2, 3, 4]), 30, 8, 2, config())
+}
+
+#[test]
+fn evaluate_scores_every_individual() {
+    let mut pop = population(1);
+    assert_eq!(pop.individuals.len(), 30);
+    assert!(pop.individuals.iter().all(|i| i.fitness.is_none()));
+    pop.evaluate(new_machine, fitness);
+    assert!(pop.individuals.iter().all(|i| i.fitness.is_some()));
+    let best = pop.best().unwrap().score();
+    assert!(pop.individuals.iter().all(|i| i.score() <= best));
+}
+
+#[test]
+fn evaluate_cases_sums_the_cases() {
+    let mut pop = population(2);
+    pop.evaluate_cases(3, new_machine, |case, _| case as f64);
+    for i in &pop.individuals {
+        assert_eq!(i.cases, vec![0.0, 1.0, 2.0]);
+        assert_eq!(i.fitness, Some(3.0));
+    }
+}
+
+#[test]
+fn individuals_too_large_for_the_machine_are_not_scored() {
+    let tiny = || machine_with(1, || 0, || 0.0);
+    let mut pop = population(4);
+    pop.evaluate(tiny, |_| panic!("fitness called on an empty machine"));
+    assert!(pop.individuals.iter().all(|i| i.fitness == Some(f64::NEG_INFINITY)));
+    pop.evaluate_cases(2, tiny, |_, _| panic!("fitness called on an empty machine"));
+    for i in &pop.individuals {
+        assert_eq!(i.cases, vec![f64::NEG_INFINITY; 2]);
+        assert_eq!(i.fitness, Some(f64::NEG_INFINITY));
+    }
+}
+
+#[test]
+fn elites_survive_unchanged() {
+    let mut pop = population(3);
+    pop.evaluate(new_machine, fitness);
+    let best = pop.best().unwrap().chromosome.clone();
+    pop.next_generation(&Tournament { size: 3 }, &CrossoverPoints);
+    assert_eq!(pop.generation, 1);
+    assert_eq!(pop.individuals.len(), 30);
+    assert_eq!(pop.individuals[0].chromosome, best);
+    assert!(pop.individuals.iter().all(|i| i.fitness.is_none()));
+}
+
+#[test]
+fn best_fitness_never_drops_with_elitism() {
+    let mut pop = population(4);
+    pop.evaluate(new_machine, fitness);
+    let mut best = pop.best().unwrap().score();
+    for _ in 0..10 {
+        pop.next_generation(&Tournament { size: 3 }, &Uniform);
+        pop.evaluate(new_machine, fitness);
+        let next = pop.best().unwrap().score();
+        assert!(next >= best);
+        best = next;
+    }
+}
+
+#[test]
+fn runs_are_reproducible() {
+    let run = |seed| {
+        let mut pop = population(seed);
+        pop.evolve(5, &Tournament { size: 3 }, &OnePoint, new_machine, fitness);
+        pop.individuals
+    };
+    assert_eq!(run(5), run(5));
+    assert!(run(5) != run(6));
+}
+
+/// Counts the crosses it makes, producing a child with a single `Nop`.
+struct Counting(Cell<usize>);
+
+impl Crossover for Counting {
+    fn cross<R: Rng>(&self, _: &Chromosome, _: &Chromosome, _: &mut R) -> Chromosome {
+        self.0.set(self.0.get() + 1);
+        Chromosome::new(vec![Gene::Op(PlainOp::Nop)], Default::default())
+    }
+}
+
+#[test]
+fn children_are_bred_by_the_given_crossover() {
+    let mut pop = Population::new_rand(XorShiftRng::from_seed([7, 2, 3, 4]),
+                                       30,
+                                       8,
+                                       2,
+                                       PopulationConfig {
+                                           elitism: 2,
+                                           crossover_rate: 1.0,
+                                           mutation_rate: 0.0,
+                                           ..config()
+                                       });
+    pop.evaluate(new_machine, fitness);
+    let counting = Counting(Cell::new(0));
+    pop.next_generation(&Tournament { size: 3 }, &counting);
+    assert_eq!(counting.0.get(), 28);
+    assert!(pop.individuals[2..].iter().all(|i| i.chromosome.genes() == [Gene::Op(PlainOp::Nop)]));
+}
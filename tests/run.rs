@@ -0,0 +1,82 @@
+extern crate gapush;
+
+mod common;
+
+use common::{machine, machine_with, provide};
+use gapush::Termination;
+use gapush::simple::*;
+
+#[test]
+fn halts_when_the_exe_stack_empties() {
+    let mut m = machine("( 1 2 Addi64 )");
+    let outcome = m.run(100);
+    match outcome.termination {
+        Termination::Halted => {}
+        t => panic!("{:?}", t),
+    }
+    assert_eq!(outcome.steps, 6);
+    // Running a block, including finishing it, is a success.
+    assert_eq!((outcome.failures, outcome.handled), (0, 0));
+    assert_eq!(m.state.int_stack(), &[3]);
+}
+
+#[test]
+fn stops_at_the_step_limit() {
+    let mut m = machine("loop( Nop )");
+    let outcome = m.run(50);
+    match outcome.termination {
+        Termination::StepLimit => {}
+        t => panic!("{:?}", t),
+    }
+    assert_eq!(outcome.steps, 50);
+    // The machine can be resumed afterwards.
+    assert_eq!(m.run(10).steps, 10);
+}
+
+#[test]
+fn stops_when_out_of_memory() {
+    let mut m = machine_with(1 << 10, || 0, || 0.0);
+    provide(&mut m, "loop( 1 )");
+    let outcome = m.run(10_000);
+    match outcome.termination {
+        Termination::OutOfMemory => {}
+        t => panic!("{:?}", t),
+    }
+    assert!(outcome.steps < 10_000);
+    assert_eq!(outcome.failures, 1);
+    assert!(outcome.peak_memory <= 1 << 10);
+    assert!(outcome.peak_memory > (1 << 10) - 64);
+}
+
+#[test]
+fn stops_when_an_instruction_is_provided() {
+    let mut m = machine("( 4 CreatePushi64 Provide 5 )");
+    let outcome = m.run(100);
+    match outcome.provided() {
+        Some(&SimpleInstruction::Pushi64(4)) => {}
+        ins => panic!("{:?}", ins),
+    }
+    assert_eq!(m.state.exe_len(), 1);
+}
+
+#[test]
+fn counts_failures_and_handled_instructions() {
+    // Popping an empty stack fails, while adding with too few operands is rescued by the int handler.
+    let mut m = machine("( Popi64 Popf64 Addi64 1 Addi64 )");
+    let outcome = m.run(100);
+    match outcome.termination {
+        Termination::Halted => {}
+        t => panic!("{:?}", t),
+    }
+    assert_eq!(outcome.failures, 2);
+    assert_eq!(outcome.handled, 1);
+    assert_eq!(m.state.int_stack(), &[1]);
+}
+
+#[test]
+fn peak_memory_outlasts_the_memory_it_measured() {
+    let mut m = machine("( i64[1 2 3 4 5 6 7 8] Popi64v )");
+    let outcome = m.run(100);
+    assert!(outcome.peak_memory >= 8 * 8);
+    assert_eq!(m.state.size(), 0);
+}
@@ -0,0 +1,112 @@
+extern crate gapush;
+extern crate rand;
+
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+use std::collections::BTreeSet;
+
+const TRIALS: usize = 6000;
+
+fn individual(fitness: Option<f64>, cases: &[f64]) -> Individual {
+    let mut i = Individual::new(Chromosome::new(Vec::new(), BTreeSet::new()));
+    i.fitness = fitness;
+    i.cases = cases.to_vec();
+    i
+}
+
+fn scored(fitnesses: &[f64]) -> Vec<Individual> {
+    fitnesses.iter().map(|&f| individual(Some(f), &[])).collect()
+}
+
+/// How often each individual is picked over many selections.
+fn counts<S: Selection>(selection: &S, individuals: &[Individual]) -> Vec<usize> {
+    let ranked = rank(individuals);
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut counts = vec![0; individuals.len()];
+    for _ in 0..TRIALS {
+        counts[selection.select(individuals, &ranked, &mut rng)] += 1;
+    }
+    counts
+}
+
+#[test]
+fn rank_orders_fittest_first() {
+    let mut individuals = scored(&[1.0, 5.0, -2.0, 3.0]);
+    individuals.push(individual(None, &[]));
+    individuals.push(individual(Some(f64::NAN), &[]));
+    let ranked = rank(&individuals);
+    assert_eq!(&ranked[..4], &[1, 3, 0, 2]);
+    // Unevaluated and NaN fitness are worse than anything else.
+    assert!(ranked[4..].contains(&4) && ranked[4..].contains(&5));
+}
+
+#[test]
+fn tournament_favours_the_fittest() {
+    let individuals = scored(&[1.0, 2.0, 3.0, 4.0]);
+    let c = counts(&Tournament { size: 3 }, &individuals);
+    assert!(c[3] > c[2] && c[2] > c[1] && c[1] > c[0]);
+    // A tournament of one is uniform.
+    let c = counts(&Tournament { size: 1 }, &individuals);
+    assert!(c.iter().all(|&n| n > TRIALS / 4 - TRIALS / 20 && n < TRIALS / 4 + TRIALS / 20));
+}
+
+#[test]
+fn roulette_never_picks_the_worst_or_unevaluated() {
+    let mut individuals = scored(&[0.0, 1.0, 3.0]);
+    individuals.push(individual(None, &[]));
+    let c = counts(&Roulette, &individuals);
+    assert_eq!(c[0], 0);
+    assert_eq!(c[3], 0);
+    // Weights of 1 and 3 above the worst.
+    assert!(c[2] > 2 * c[1] && c[2] < 4 * c[1]);
+
+    let unevaluated = vec![individual(None, &[]), individual(None, &[])];
+    let c = counts(&Roulette, &unevaluated);
+    assert!(c[0] > 0 && c[1] > 0);
+}
+
+#[test]
+fn rank_picks_in_proportion_to_rank() {
+    // The ranks are 2, 3 and 1, regardless of how far apart the fitnesses are.
+    let individuals = scored(&[0.0, 1000.0, -1000.0]);
+    let c = counts(&Rank, &individuals);
+    let expected = [2 * TRIALS / 6, 3 * TRIALS / 6, TRIALS / 6];
+    for (&n, &e) in c.iter().zip(&expected) {
+        assert!(n > e - TRIALS / 30 && n < e + TRIALS / 30, "{:?}", c);
+    }
+
+    let one = scored(&[1.0]);
+    assert_eq!(counts(&Rank, &one), vec![TRIALS]);
+}
+
+#[test]
+fn truncation_only_picks_the_fittest() {
+    let individuals = scored(&[1.0, 4.0, 2.0, 3.0]);
+    let c = counts(&Truncation { count: 2 }, &individuals);
+    assert_eq!(c[0] + c[2], 0);
+    assert!(c[1] > 0 && c[3] > 0);
+    // A count past the population is clamped.
+    let c = counts(&Truncation { count: 10 }, &individuals);
+    assert!(c.iter().all(|&n| n > 0));
+}
+
+#[test]
+fn lexicase_picks_specialists() {
+    let individuals = vec![
+        // Best on the first case.
+        individual(None, &[10.0, 0.0, 0.0]),
+        // Best on the second case.
+        individual(None, &[0.0, 10.0, 0.0]),
+        // Best on the third case.
+        individual(None, &[0.0, 0.0, 10.0]),
+        // Better on average, but never the best on any case.
+        individual(None, &[9.0, 9.0, 9.0]),
+    ];
+    let c = counts(&Lexicase { epsilon: 0.0 }, &individuals);
+    assert_eq!(c[3], 0);
+    assert!(c[..3].iter().all(|&n| n > TRIALS / 4));
+
+    // Within epsilon of the best, the generalist survives every case.
+    let c = counts(&Lexicase { epsilon: 1.0 }, &individuals);
+    assert!(c[3] > 0);
+}
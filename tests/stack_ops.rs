@@ -0,0 +1,78 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine, machine_with};
+use gapush::Fault;
+use gapush::simple::*;
+
+/// Run `src` to completion, checking that nothing failed.
+fn run(src: &str) -> TestMachine {
+    let mut m = machine(src);
+    assert_eq!(m.run(1000).failures, 0, "{}", src);
+    m
+}
+
+#[test]
+fn depth_dup_swap_and_flush() {
+    let m = run("( 1.0 2.0 Depthf64 Dupf64 'a' 'b' Swapc true Flushb Depthb )");
+    assert_eq!(m.state.int_stack(), &[2, 0]);
+    assert_eq!(m.state.float_stack(), &[1.0, 2.0, 2.0]);
+    assert_eq!(m.state.char_stack(), &['b', 'a']);
+
+    // The int stack's depth doesn't count the depth itself.
+    let m = run("( 7 7 Depthi64 )");
+    assert_eq!(m.state.int_stack(), &[7, 7, 2]);
+
+    let m = run("( \"x\" Flushs i64[1] Flushi64v )");
+    assert_eq!(m.state.size(), 0);
+}
+
+#[test]
+fn shove_undoes_rot() {
+    let m = run("( 'a' 'b' 'c' 'd' 2 Rotc 2 Shovec )");
+    assert_eq!(m.state.char_stack(), &['a', 'b', 'c', 'd']);
+
+    let m = run("( 1 2 3 4 2 Shovei64 )");
+    assert_eq!(m.state.int_stack(), &[1, 4, 2, 3]);
+
+    // Positions past the bottom of the stack are clamped, as with Yank.
+    let m = run("( 'a' 'b' 'c' 9 Shovec )");
+    assert_eq!(m.state.char_stack(), &['c', 'a', 'b']);
+}
+
+#[test]
+fn yank() {
+    let m = run("( 'a' 'b' 'c' 'd' 2 Yankc 1 YankDupc )");
+    assert_eq!(m.state.char_stack(), &['a', 'c', 'd', 'b', 'd']);
+
+    // Positions past the bottom of the stack are clamped, unlike with Rot and Copy.
+    let m = run("( \"a\" \"b\" 9 Yanks 9 YankDups )");
+    assert_eq!(m.state.string_stack(), &["b".to_string(), "a".to_string(), "b".to_string()]);
+
+    // The position is popped before yanking on the int stack.
+    let m = run("( 10 20 30 2 Yanki64 1 YankDupi64 )");
+    assert_eq!(m.state.int_stack(), &[20, 30, 10, 30]);
+
+    let mut m = machine("3");
+    for &ins in &["2", "Yankexe", "1"] {
+        m.provide(ins.parse().unwrap());
+    }
+    assert_eq!(m.run(100).failures, 0);
+    assert_eq!(m.state.int_stack(), &[3, 2]);
+
+    let mut m = machine("( 0 YankDupb )");
+    assert_eq!(m.run(100).failures, 1);
+    assert!(m.state.bool_stack().is_empty());
+}
+
+#[test]
+fn yank_dup_is_charged_against_memory() {
+    let mut m = machine_with(1 << 10, || 0, || 0.0);
+    m.state.push_int_vecs(&[&[0; 100], &[]]).unwrap();
+    let size = m.state.size();
+    m.state.push_int(1).unwrap();
+    m.provide(SimpleInstruction::PlainOp(PlainOp::YankDupi64v));
+    assert_eq!(m.cycle().1, Err(Fault::Full));
+    assert_eq!(m.state.size(), size);
+}
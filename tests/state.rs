@@ -0,0 +1,75 @@
+extern crate gapush;
+
+use gapush::{SizeError, Stack, State};
+use gapush::simple::SimpleInstruction;
+
+fn state() -> State<SimpleInstruction> {
+    State::new(1 << 16)
+}
+
+#[test]
+fn seeding_leaves_the_last_value_on_top() {
+    let mut s = state();
+    s.push_ints(&[1, 2, 3]).unwrap();
+    s.push_floats(&[0.5]).unwrap();
+    s.push_bools(&[true, false]).unwrap();
+    s.push_strings(&["a", "bc"]).unwrap();
+    s.push_chars(&['x', 'y']).unwrap();
+    s.push_int_vecs(&[&[1, 2], &[]]).unwrap();
+    s.push_float_vecs(&[&[1.5]]).unwrap();
+    s.push_bool_vecs(&[&[true], &[false, true]]).unwrap();
+
+    assert_eq!(s.int_stack(), &[1, 2, 3]);
+    assert_eq!(s.float_stack(), &[0.5]);
+    assert_eq!(s.bool_stack(), &[true, false]);
+    assert_eq!(s.string_stack(), &["a".to_string(), "bc".to_string()]);
+    assert_eq!(s.char_stack(), &['x', 'y']);
+    assert_eq!(s.int_vec_stack().iter().map(|v| v.as_slice()).collect::<Vec<_>>(),
+               vec![&[1, 2][..], &[]]);
+    assert_eq!(s.float_vec_stack()[0].as_slice(), &[1.5]);
+    assert_eq!(s.bool_vec_stack()[1].as_slice(), &[false, true]);
+
+    assert_eq!(s.pop_int(), Some(3));
+    assert_eq!(s.pop_char(), Some('y'));
+    assert_eq!(s.pop_int_vec().map(|v| v.len()), Some(0));
+}
+
+#[test]
+fn lengths_and_depths_agree() {
+    let mut s = state();
+    s.push_ints(&[1, 2]).unwrap();
+    s.push_chars(&['a']).unwrap();
+    s.push_bool_vecs(&[&[], &[], &[]]).unwrap();
+    s.push_exe(SimpleInstruction::Pushi64(1)).unwrap();
+
+    let depths = s.depths();
+    assert_eq!((s.int_len(), s.char_len(), s.bool_vec_len(), s.exe_len()), (2, 1, 3, 1));
+    assert_eq!(depths.get(Stack::Int), 2);
+    assert_eq!(depths.get(Stack::Char), 1);
+    assert_eq!(depths.get(Stack::BoolVec), 3);
+    assert_eq!(depths.get(Stack::Exe), 1);
+    assert_eq!(depths.get(Stack::Float), 0);
+}
+
+#[test]
+fn size_tracks_everything_on_the_stacks() {
+    let mut s = state();
+    assert_eq!(s.size(), 0);
+    s.push_int_vecs(&[&[1, 2, 3, 4]]).unwrap();
+    s.push_strings(&["some text"]).unwrap();
+    assert!(s.size() > 4 * 8 + 9);
+    s.pop_int_vec();
+    s.pop_string();
+    assert_eq!(s.size(), 0);
+}
+
+#[test]
+fn seeding_stops_when_full() {
+    let mut s = State::<SimpleInstruction>::new(20);
+    assert_eq!(s.push_ints(&[1, 2, 3]), Err(SizeError::Full));
+    // Those which fit are kept.
+    assert_eq!(s.int_stack(), &[1, 2]);
+    assert_eq!(s.size(), 16);
+    assert_eq!(s.push_int_vecs(&[&[1, 2, 3]]), Err(SizeError::Full));
+    assert_eq!(s.int_vec_len(), 0);
+}
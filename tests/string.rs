@@ -0,0 +1,86 @@
+extern crate gapush;
+
+mod common;
+
+use common::{TestMachine, machine_with, new_machine};
+use gapush::Fault;
+use gapush::simple::*;
+
+/// Run `src` to completion, checking that nothing failed.
+fn run(src: &str) -> TestMachine {
+    let mut m = new_machine();
+    m.provide(src.parse().unwrap());
+    let outcome = m.run(1000);
+    assert_eq!(outcome.failures, 0, "{}", src);
+    m
+}
+
+fn strings(m: &TestMachine) -> Vec<&str> {
+    m.state.string_stack().iter().map(|s| &s[..]).collect()
+}
+
+#[test]
+fn concat_length_and_substrings() {
+    let m = run(r#"( "ab" "cd" Concats )"#);
+    assert_eq!(strings(&m), ["abcd"]);
+
+    // Lengths and positions count chars, not bytes.
+    let m = run(r#"( "h\u{e9}llo" Lens "h\u{e9}llo" 1 3 Substrs "h\u{e9}llo" "llo" Indexofs "a" "z" Indexofs )"#);
+    assert_eq!(m.state.int_stack(), &[5, 2, -1]);
+    assert_eq!(strings(&m), ["\u{e9}l"]);
+
+    // Out of range bounds are clamped.
+    let m = run(r#"( "abc" 2 100 Substrs "abc" 2 0 Substrs )"#);
+    assert_eq!(strings(&m), ["c", ""]);
+}
+
+#[test]
+fn splitting() {
+    let m = run(r#"( "a,b,,c" "," Splits )"#);
+    assert_eq!(strings(&m), ["c", "", "b", "a"]);
+
+    let m = run(r#"( " one  two " "" Splits )"#);
+    assert_eq!(strings(&m), ["two", "one"]);
+}
+
+#[test]
+fn splitting_too_much_leaves_no_parts() {
+    let mut m = machine_with(1 << 10, || 0, || 0.0);
+    m.state.push_strings(&[&"x,".repeat(300)[..], ","]).unwrap();
+    m.provide(SimpleInstruction::PlainOp(PlainOp::Splits));
+    assert_eq!(m.cycle().1, Err(Fault::Full));
+    assert_eq!(m.state.string_len(), 0);
+}
+
+#[test]
+fn chars() {
+    let m = run(r#"( "abc" 1 Charats 'z' Ctos 97 Itoc 'a' Ctoi )"#);
+    assert_eq!(strings(&m), ["abc", "z"]);
+    assert_eq!(m.state.char_stack(), &['b', 'a']);
+    assert_eq!(m.state.int_stack(), &[97]);
+
+    let mut m = new_machine();
+    m.provide(r#"( "abc" 3 Charats )"#.parse().unwrap());
+    assert_eq!(m.run(100).failures, 1);
+    assert_eq!(strings(&m), ["abc"]);
+    assert!(m.state.char_stack().is_empty());
+}
+
+#[test]
+fn comparisons() {
+    let m = run(r#"( "a" "b" Less "a" "b" Grts "a" "a" Eqs "a" "a" Neqs 'a' 'b' Lesc 'a' 'b' Grtc 'c' 'c' Eqc 'c' 'd' Neqc )"#);
+    assert_eq!(m.state.bool_stack(), &[true, false, true, false, true, false, true, true]);
+}
+
+#[test]
+fn stack_manipulation() {
+    let m = run(r#"( "a" "b" "c" 2 Rots "x" 0 Copys Pops 'p' 'q' 1 Copyc Popc 'r' 1 Rotc )"#);
+    assert_eq!(strings(&m), ["b", "c", "a", "x"]);
+    assert_eq!(m.state.char_stack(), &['p', 'r', 'q']);
+
+    let m = run(r#"( "s" CreatePushs 'c' CreatePushc )"#);
+    match m.state.ins_stack() {
+        &[SimpleInstruction::Pushs(ref s), SimpleInstruction::Pushc('c')] if s == "s" => {}
+        ins => panic!("{:?}", ins),
+    }
+}
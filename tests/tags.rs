@@ -0,0 +1,84 @@
+extern crate gapush;
+extern crate rand;
+
+mod common;
+
+use common::{TestMachine, machine};
+use gapush::simple::*;
+use rand::{SeedableRng, XorShiftRng};
+
+/// Run `src` to completion, checking that nothing failed.
+///
+/// A `Tag` stores the next top level instruction of `src`, so it can't be used inside a block.
+fn run(src: &str) -> TestMachine {
+    let mut m = machine(src);
+    assert_eq!(m.run(1000).failures, 0, "{}", src);
+    m
+}
+
+#[test]
+fn tag_refs_run_the_closest_tag() {
+    let m = run("tag:10 ( 1 ) tag:20 2 ref:10 ref:11 ref:20 ref:21 ref:0");
+    assert_eq!(m.state.int_stack(), &[1, 2, 2, 1, 1]);
+    assert_eq!(m.state.tags().len(), 2);
+
+    // Tagging again replaces the instruction.
+    let m = run("tag:5 1 tag:5 2 ref:5");
+    assert_eq!(m.state.int_stack(), &[2]);
+
+    let mut m = machine("ref:1");
+    assert_eq!(m.run(10).failures, 1);
+}
+
+#[test]
+fn tags_can_be_constructed() {
+    let m = run("7 CreateTag 7 CreateTagRef");
+    match m.state.ins_stack() {
+        [SimpleInstruction::Tag(7), SimpleInstruction::TagRef(7)] => {}
+        ins => panic!("{:?}", ins),
+    }
+
+    let m = run("3 CreateTag Call 5 ref:0");
+    assert_eq!(m.state.int_stack(), &[5]);
+}
+
+#[test]
+fn flushing_tags_releases_their_memory() {
+    let mut m = machine("tag:1 i64[1 2 3 4 5 6 7 8]");
+    assert_eq!(m.run(100).failures, 0);
+    assert!(m.state.size() > 8 * 8);
+    m.provide("Flushtags".parse().unwrap());
+    assert_eq!(m.run(100).failures, 0);
+    assert!(m.state.tags().is_empty());
+    assert_eq!(m.state.size(), 0);
+}
+
+#[test]
+fn tag_genes() {
+    assert!(matches!(Gene::Tag(3).instruction(), Some(SimpleInstruction::Tag(3))));
+    assert!(matches!(Gene::TagRef(4).instruction(), Some(SimpleInstruction::TagRef(4))));
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    for _ in 0..100 {
+        match (Literal::Tag { low: 5, high: 8 }).generate(&mut rng) {
+            Gene::Tag(n) => assert!((5..8).contains(&n)),
+            g => panic!("{:?}", g),
+        }
+        match (Literal::TagRef { low: 0, high: 2 }).generate(&mut rng) {
+            Gene::TagRef(n) => assert!(n < 2),
+            g => panic!("{:?}", g),
+        }
+    }
+
+    // Perturbed tags stay in range.
+    let mut low = Gene::Tag(0);
+    let mut high = Gene::TagRef(u32::MAX);
+    for _ in 0..100 {
+        low.perturb(3, 0.1, &mut rng);
+        high.perturb(3, 0.1, &mut rng);
+    }
+    match (low, high) {
+        (Gene::Tag(a), Gene::TagRef(b)) => assert!(a <= 300 && b >= u32::MAX - 300),
+        g => panic!("{:?}", g),
+    }
+}
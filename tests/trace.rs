@@ -0,0 +1,70 @@
+extern crate gapush;
+
+mod common;
+
+use common::machine;
+use gapush::{Fault, StackDepths, Tracer};
+use gapush::simple::*;
+
+/// Records everything the tracer hooks are called with.
+#[derive(Default)]
+struct Recorder {
+    before: Vec<(String, usize, StackDepths)>,
+    after: Vec<(Result<(), Fault>, bool, usize, StackDepths)>,
+}
+
+impl Tracer<SimpleInstruction> for Recorder {
+    fn before(&mut self, ins: &SimpleInstruction, memory: usize, depths: &StackDepths) {
+        self.before.push((ins.to_string(), memory, *depths));
+    }
+
+    fn after(&mut self,
+             result: Result<(), Fault>,
+             provided: Option<&SimpleInstruction>,
+             memory: usize,
+             depths: &StackDepths) {
+        self.after.push((result, provided.is_some(), memory, *depths));
+    }
+}
+
+#[test]
+fn hooks_surround_every_instruction() {
+    let mut m = machine("( 1 2 Addi64 )");
+    let mut recorder = Recorder::default();
+    for _ in 0..6 {
+        assert!(m.cycle_traced(&mut recorder).1.is_ok());
+    }
+    let executed = recorder.before.iter().map(|b| &b.0[..]).collect::<Vec<_>>();
+    assert_eq!(executed, ["(1 2 Addi64)", "1", "(2 Addi64)", "2", "(Addi64)", "Addi64"]);
+    assert_eq!(recorder.after.len(), 6);
+    assert!(recorder.after.iter().all(|a| a.0.is_ok() && !a.1));
+
+    // Before `Addi64` both operands are on the stack, and after it only the sum is.
+    assert_eq!(recorder.before[5].2.int, 2);
+    assert_eq!(recorder.after[5].3.int, 1);
+    assert!(recorder.after[5].2 < recorder.before[4].1);
+    assert_eq!(m.state.int_stack(), &[3]);
+}
+
+#[test]
+fn hooks_see_faults_and_provided_instructions() {
+    let mut m = machine("( Popi64 1 CreatePushi64 Provide )");
+    let mut recorder = Recorder::default();
+    let outcome = m.run_traced(100, &mut recorder);
+    assert!(outcome.provided().is_some());
+    assert_eq!(recorder.after[1].0, Err(Fault::Empty));
+    assert!(recorder.after.last().unwrap().1);
+}
+
+#[test]
+fn op_counter_counts_ops_and_faults() {
+    let mut m = machine("( 1 Inci64 Inci64 Popi64 Popi64 2.0 )");
+    let mut counter = OpCounter::new();
+    m.run_traced(100, &mut counter);
+    assert_eq!(counter.ops.get(&PlainOp::Inci64), Some(&2));
+    assert_eq!(counter.ops.get(&PlainOp::Popi64), Some(&2));
+    assert_eq!(counter.faults.get(&Fault::Empty), Some(&1));
+    // The block runs once per element and the two literals run once each.
+    assert_eq!(counter.other, 6 + 2);
+    assert_eq!(counter.total(), 12);
+}